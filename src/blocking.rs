@@ -0,0 +1,120 @@
+//! A synchronous client for the Thunderstore API.
+//!
+//! The [`Client`] in this module wraps the async [`crate::Client`] and drives it on a private
+//! single-threaded runtime, so it can be used without setting up tokio yourself.
+//!
+//! This module requires the `blocking` feature to be enabled.
+//!
+//! Note that the blocking client must not be used from within an async runtime,
+//! since it will panic when trying to block the current thread.
+
+use crate::{
+    models::*, usermedia::PackageMetadata, ClientBuilder, IntoPackageId, IntoVersionId, Result,
+};
+use bytes::Bytes;
+use std::{fmt::Display, future::Future, path::Path};
+use tokio::runtime::{self, Runtime};
+
+/// A blocking client for interacting with the Thunderstore API.
+///
+/// The easiest way to create a client is to use the [`Client::new`] method.
+/// If you need more control over the client's configuration, use [`ClientBuilder::build_blocking`] instead.
+///
+/// ## Example
+///
+/// ```no_run
+/// let client = thunderstore::blocking::Client::new();
+///
+/// let package = client.get_package(("Kesomannen", "GaleModManager"))?;
+/// let bytes = client.download(&package.latest)?;
+/// # Ok::<(), thunderstore::Error>(())
+/// ```
+pub struct Client {
+    inner: crate::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Creates a new blocking client with the default configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying runtime cannot be created.
+    /// Use [`ClientBuilder::build_blocking`] to handle this error instead.
+    pub fn new() -> Self {
+        Self::from_async(crate::Client::new()).expect("failed to create runtime")
+    }
+
+    /// Creates a [`ClientBuilder`] to configure a new client.
+    ///
+    /// Finish the builder with [`ClientBuilder::build_blocking`] to get a blocking client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    fn from_async(inner: crate::Client) -> Result<Self> {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self { inner, runtime })
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Fetches information about a single package.
+    ///
+    /// See [`crate::Client::get_package`] for more information.
+    pub fn get_package(&self, id: impl IntoPackageId) -> Result<Package> {
+        self.block_on(self.inner.get_package(id))
+    }
+
+    /// Fetches information about a specific version of a package.
+    ///
+    /// See [`crate::Client::get_version`] for more information.
+    pub fn get_version(&self, id: impl IntoVersionId) -> Result<PackageVersion> {
+        self.block_on(self.inner.get_version(id))
+    }
+
+    /// Downloads a package from Thunderstore.
+    ///
+    /// See [`crate::Client::download`] for more information.
+    pub fn download(&self, version: impl IntoVersionId) -> Result<Bytes> {
+        self.block_on(self.inner.download(version))
+    }
+
+    /// Fetches all available packages in a community and collects them in a `Vec`.
+    ///
+    /// See [`crate::Client::list_packages_v1`] for more information.
+    pub fn list_packages_v1(&self, community: impl Display) -> Result<Vec<PackageV1>> {
+        self.block_on(self.inner.list_packages_v1(community))
+    }
+
+    /// Uploads and submits a package.
+    ///
+    /// See [`crate::Client::publish_file`] for more information.
+    pub fn publish_file(
+        &self,
+        path: impl AsRef<Path>,
+        metadata: PackageMetadata,
+    ) -> Result<PackageSubmissionResult> {
+        self.block_on(self.inner.publish_file(path, metadata))
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    /// Builds a blocking client with the configured options.
+    ///
+    /// This method requires the `blocking` feature to be enabled.
+    pub fn build_blocking(self) -> Result<Client> {
+        Client::from_async(self.build()?)
+    }
+}
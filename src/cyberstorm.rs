@@ -0,0 +1,53 @@
+use crate::{models::*, Client, ResponseJsonExt, Result};
+use reqwest::Method;
+use serde::Deserialize;
+use std::fmt::Display;
+
+impl Client {
+    /// Fetches information about a team.
+    ///
+    /// - `team` is the name of the team, which is also the namespace of its packages.
+    ///
+    /// Returns [`Error::NotFound`](crate::Error::NotFound) if there is no team with the given name.
+    pub async fn get_team(&self, team: impl Display) -> Result<Team> {
+        let url = self.cyberstorm_url(format_args!("team/{}", team));
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
+        Ok(response)
+    }
+
+    /// Fetches the members of a team.
+    ///
+    /// - `team` is the name of the team, which is also the namespace of its packages.
+    ///
+    /// This method requires a valid API token on the client, belonging to a member of the team.
+    pub async fn list_team_members(&self, team: impl Display) -> Result<Vec<TeamMember>> {
+        let url = self.cyberstorm_url(format_args!("team/{}/member", team));
+        let response = self
+            .send(self.auth_request(Method::GET, url)?)
+            .await?
+            .parse_json()
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Fetches the number of packages listed in a community, without fetching the packages themselves.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    pub async fn get_package_count(&self, community: impl Display) -> Result<u64> {
+        #[derive(Deserialize)]
+        struct CommunityCounts {
+            total_package_count: u64,
+        }
+
+        let url = self.cyberstorm_url(format_args!("community/{}", community));
+        let response: CommunityCounts =
+            self.send(self.client.get(&url)).await?.parse_json().await?;
+
+        Ok(response.total_package_count)
+    }
+
+    pub(crate) fn cyberstorm_url(&self, tail: impl Display) -> String {
+        format!("{}/api/cyberstorm/{}/", self.base_url, tail)
+    }
+}
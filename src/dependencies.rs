@@ -0,0 +1,323 @@
+//! Resolution of package dependencies.
+
+use crate::{
+    models::PackageVersion, Client, IntoVersionId, PackageId, Result, VersionId, BATCH_CONCURRENCY,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+impl Client {
+    /// Resolves all dependencies of a package version, including transitive ones.
+    ///
+    /// Each package appears at most once in the result. If multiple packages depend on different
+    /// versions of the same package, the highest requested version is picked.
+    ///
+    /// The returned list is sorted so that every package comes after its dependencies, which
+    /// means it can be installed in order. The requested version itself is the last element.
+    /// Circular dependencies are allowed, but the order of the packages in a cycle is unspecified.
+    pub async fn resolve_dependencies(
+        &self,
+        version: impl IntoVersionId,
+    ) -> Result<Vec<PackageVersion>> {
+        let root = version.into_id()?;
+        let mut versions = self.fetch_dependency_tree(&root).await?;
+
+        let dependencies = dependency_map(&versions)?;
+        let order = install_order(&root, &dependencies)?;
+
+        Ok(order
+            .into_iter()
+            .filter_map(|id| versions.remove(&id))
+            .collect())
+    }
+
+    /// Builds a graph of a package version and all of its dependencies, including transitive ones.
+    ///
+    /// Unlike [`Client::resolve_dependencies`], versions are not deduplicated by package,
+    /// so if two packages depend on different versions of the same package, both are included.
+    /// See [`DependencyGraph`] for more information.
+    pub async fn dependency_graph(&self, version: impl IntoVersionId) -> Result<DependencyGraph> {
+        let root = version.into_id()?;
+        let nodes = self.fetch_dependency_tree(&root).await?;
+        let edges = dependency_map(&nodes)?;
+
+        Ok(DependencyGraph { nodes, edges })
+    }
+
+    /// Downloads a package version and all of its dependencies into `dest_dir`, which is created if needed.
+    ///
+    /// The dependencies are resolved like in [`Client::resolve_dependencies`], and each archive is
+    /// saved as `{dest_dir}/{namespace}-{name}-{version}.zip`, like in [`Client::download_to_dir`].
+    /// At most [`BATCH_CONCURRENCY`] downloads are in flight at once.
+    ///
+    /// Returns the id and path of each downloaded archive, in installation order.
+    pub async fn download_with_dependencies(
+        &self,
+        version: impl IntoVersionId,
+        dest_dir: impl AsRef<Path>,
+    ) -> Result<Vec<(VersionId, PathBuf)>> {
+        let dest_dir = dest_dir.as_ref();
+        let versions = self.resolve_dependencies(version).await?;
+
+        tokio::fs::create_dir_all(dest_dir).await?;
+
+        stream::iter(versions)
+            .map(|version| async move {
+                let id = VersionId::from(&version);
+                let path = crate::archive_path(dest_dir, &id);
+                self.download_to_file(id.clone(), &path).await?;
+                Ok((id, path))
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Returns the total size in bytes of a package version and all of its dependencies,
+    /// which is how much [`Client::download_with_dependencies`] would download.
+    ///
+    /// The dependencies are resolved like in [`Client::resolve_dependencies`], and the size of
+    /// each version is looked up like in [`Client::version_file_size`], with at most
+    /// [`BATCH_CONCURRENCY`] requests in flight at once. If any size is missing from the
+    /// responses, the package index is fetched once and used for all of them.
+    pub async fn dependency_closure_size(&self, version: impl IntoVersionId) -> Result<u64> {
+        let ids: Vec<_> = self
+            .resolve_dependencies(version)
+            .await?
+            .iter()
+            .map(VersionId::from)
+            .collect();
+
+        let sizes: Vec<_> = stream::iter(&ids)
+            .map(|id| self.content_length(id))
+            .buffered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        // fetch the index at most once, instead of once per missing size
+        let index = if sizes.contains(&None) {
+            self.get_package_index_cached().await?
+        } else {
+            Default::default()
+        };
+
+        ids.iter()
+            .zip(sizes)
+            .map(|(id, size)| match size {
+                Some(size) => Ok(size),
+                None => crate::index_file_size(&index, id),
+            })
+            .sum()
+    }
+
+    /// Fetches `root` and every version it transitively depends on, one level at a time,
+    /// with at most [`BATCH_CONCURRENCY`] requests in flight at once.
+    pub(crate) async fn fetch_dependency_tree(
+        &self,
+        root: &VersionId,
+    ) -> Result<HashMap<VersionId, PackageVersion>> {
+        let mut versions = HashMap::new();
+        let mut seen = HashSet::from([root.clone()]);
+        let mut queue = vec![root.clone()];
+
+        while !queue.is_empty() {
+            let fetched: Vec<_> = stream::iter(&queue)
+                .map(|id| self.get_version(id.clone()))
+                .buffered(BATCH_CONCURRENCY)
+                .try_collect()
+                .await?;
+            let mut next = Vec::new();
+
+            for (id, version) in queue.drain(..).zip(fetched) {
+                for dependency in &version.dependencies {
+                    let dependency: VersionId = dependency.parse()?;
+                    if seen.insert(dependency.clone()) {
+                        next.push(dependency);
+                    }
+                }
+
+                versions.insert(id, version);
+            }
+
+            queue = next;
+        }
+
+        Ok(versions)
+    }
+}
+
+/// A graph of package versions and their dependencies.
+///
+/// Each node is a package version, and there is an edge from every version to each of its
+/// dependencies. Created with [`Client::dependency_graph`].
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    nodes: HashMap<VersionId, PackageVersion>,
+    edges: HashMap<VersionId, Vec<VersionId>>,
+}
+
+impl DependencyGraph {
+    /// Returns an iterator over all versions in the graph, in arbitrary order.
+    pub fn nodes(&self) -> impl Iterator<Item = (&VersionId, &PackageVersion)> {
+        self.nodes.iter()
+    }
+
+    /// Returns an iterator over all edges in the graph, as `(dependent, dependency)` pairs.
+    pub fn edges(&self) -> impl Iterator<Item = (&VersionId, &VersionId)> {
+        self.edges
+            .iter()
+            .flat_map(|(id, dependencies)| dependencies.iter().map(move |dep| (id, dep)))
+    }
+
+    /// Returns the version with the given id, if it's in the graph.
+    pub fn get(&self, id: &VersionId) -> Option<&PackageVersion> {
+        self.nodes.get(id)
+    }
+
+    /// Returns the number of versions in the graph.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the graph contains no versions.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the versions that no other version in the graph depends on.
+    ///
+    /// For a graph created with [`Client::dependency_graph`], this is usually just the
+    /// requested version, unless it's part of a dependency cycle.
+    pub fn roots(&self) -> Vec<&VersionId> {
+        let dependencies: HashSet<_> = self.edges.values().flatten().collect();
+
+        self.nodes
+            .keys()
+            .filter(|id| !dependencies.contains(id))
+            .collect()
+    }
+
+    /// Returns the direct dependencies of a version.
+    pub fn dependencies_of(&self, id: &VersionId) -> &[VersionId] {
+        self.edges.get(id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the versions which directly depend on the given version.
+    pub fn dependents_of(&self, id: &VersionId) -> Vec<&VersionId> {
+        self.edges
+            .iter()
+            .filter(|(_, dependencies)| dependencies.contains(id))
+            .map(|(dependent, _)| dependent)
+            .collect()
+    }
+
+    /// Returns `true` if the graph contains a dependency cycle.
+    pub fn is_cyclic(&self) -> bool {
+        let mut finished = HashSet::new();
+        let mut in_progress = HashSet::new();
+
+        self.nodes
+            .keys()
+            .any(|id| self.has_cycle_from(id, &mut in_progress, &mut finished))
+    }
+
+    fn has_cycle_from<'a>(
+        &'a self,
+        id: &'a VersionId,
+        in_progress: &mut HashSet<&'a VersionId>,
+        finished: &mut HashSet<&'a VersionId>,
+    ) -> bool {
+        if finished.contains(id) {
+            return false;
+        }
+
+        if !in_progress.insert(id) {
+            return true;
+        }
+
+        let cyclic = self
+            .dependencies_of(id)
+            .iter()
+            .any(|dependency| self.has_cycle_from(dependency, in_progress, finished));
+
+        in_progress.remove(id);
+        finished.insert(id);
+
+        cyclic
+    }
+}
+
+/// Parses the dependency strings of each version.
+pub(crate) fn dependency_map(
+    versions: &HashMap<VersionId, PackageVersion>,
+) -> Result<HashMap<VersionId, Vec<VersionId>>> {
+    versions
+        .iter()
+        .map(|(id, version)| {
+            let dependencies = version
+                .dependencies
+                .iter()
+                .map(|dependency| dependency.parse())
+                .collect::<Result<_>>()?;
+
+            Ok((id.clone(), dependencies))
+        })
+        .collect()
+}
+
+/// Picks the highest requested version of each package and returns the versions
+/// reachable from `root`, with dependencies ordered before their dependents.
+pub(crate) fn install_order(
+    root: &VersionId,
+    dependencies: &HashMap<VersionId, Vec<VersionId>>,
+) -> Result<Vec<VersionId>> {
+    let mut chosen: HashMap<PackageId, (semver::Version, &VersionId)> = HashMap::new();
+
+    for id in dependencies.values().flatten() {
+        let version = id.parsed_version()?;
+
+        match chosen.get(&PackageId::from(id)) {
+            Some((current, _)) if *current >= version => (),
+            _ => {
+                chosen.insert(id.into(), (version, id));
+            }
+        }
+    }
+
+    let chosen = chosen
+        .into_iter()
+        .map(|(package, (_, id))| (package, id))
+        .collect::<HashMap<_, _>>();
+
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    visit(root, dependencies, &chosen, &mut visited, &mut order);
+
+    Ok(order)
+}
+
+fn visit(
+    id: &VersionId,
+    dependencies: &HashMap<VersionId, Vec<VersionId>>,
+    chosen: &HashMap<PackageId, &VersionId>,
+    visited: &mut HashSet<PackageId>,
+    order: &mut Vec<VersionId>,
+) {
+    // packages are marked before their dependencies are visited, which breaks cycles
+    if !visited.insert(id.into()) {
+        return;
+    }
+
+    for dependency in dependencies.get(id).into_iter().flatten() {
+        let dependency = chosen
+            .get(&PackageId::from(dependency))
+            .copied()
+            .unwrap_or(dependency);
+        visit(dependency, dependencies, chosen, visited, order);
+    }
+
+    order.push(id.clone());
+}
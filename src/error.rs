@@ -1,8 +1,32 @@
 /// Represents an error that can occur when interacting with the API.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
+    /// The connection to the server couldn't be established, for example because of a DNS
+    /// failure, or because the server refused the connection.
+    ///
+    /// TLS failures are reported as [`Error::Tls`] instead.
+    #[error("Failed to connect: {0}")]
+    Connect(reqwest::Error),
+
+    /// The TLS handshake with the server failed, for example because its certificate is invalid
+    /// or the server doesn't speak TLS.
+    ///
+    /// Unlike [`Error::Connect`], this is not [transient](Error::is_transient), since
+    /// retrying usually fails the same way.
+    #[error("TLS error: {0}")]
+    Tls(reqwest::Error),
+
+    /// The request timed out.
+    #[error("Request timed out: {0}")]
+    Timeout(reqwest::Error),
+
+    /// The response body couldn't be decoded.
+    #[error("Failed to decode response: {0}")]
+    Decode(reqwest::Error),
+
+    /// Any other error from the HTTP client.
     #[error("Reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(reqwest::Error),
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -13,26 +37,204 @@ pub enum Error {
     #[error("Failed to parse JSON: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// A response couldn't be deserialized, most likely because the API changed.
+    ///
+    /// `body` contains the start of the response, truncated to a few KB.
+    #[error("Failed to parse response: {source}, body: {body}")]
+    Deserialize {
+        source: serde_json::Error,
+        body: String,
+    },
+
+    #[error("Failed to parse YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
     #[error("Failed to decode base64: {0}")]
     Base64(#[from] base64::DecodeError),
 
+    #[cfg(feature = "zip")]
+    #[error("Failed to read ZIP archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("ZIP archive contains an unsafe path: {0}")]
+    UnsafeArchivePath(String),
+
+    #[error("No manifest.json found in package")]
+    ManifestNotFound,
+
+    #[error("Could not determine the file size of the package")]
+    UnknownFileSize,
+
+    #[error("No export.r2x found in profile")]
+    ProfileManifestNotFound,
+
     #[error("Invalid legacyprofile data")]
     InvalidProfileData,
 
     #[error("No upload UUID returned from server")]
     NoUploadUuidGiven,
 
+    #[error("Completed upload parts don't match the upload URLs")]
+    InvalidUploadParts,
+
+    /// An upload part doesn't match the data, either because the server returned a range outside
+    /// of the uploaded data, or because the part doesn't exist or got data of the wrong length.
+    #[error("Invalid upload part {part_number}")]
+    InvalidUploadPart { part_number: u32 },
+
+    /// The response to uploading a part didn't have an ETag header, or it wasn't valid ASCII.
+    #[error("No valid ETag returned for upload part {part_number}")]
+    MissingETag { part_number: u32 },
+
+    /// An upload was submitted before it was finished, see
+    /// [`Client::submit_existing`](crate::Client::submit_existing).
+    #[error("Upload is not complete, its status is {status:?}")]
+    UploadNotComplete {
+        status: crate::models::UserMediaStatus,
+    },
+
+    /// The server responded with `304 Not Modified` to a request which had no cached response
+    /// to fall back on, for example because a request hook or proxy added its own conditional headers.
+    #[error("Server responded with 304 Not Modified, but nothing was cached")]
+    UnexpectedNotModified,
+
     #[error("An API token is required to perform this action")]
     ApiTokenRequired,
 
     #[error("API token is invalid")]
     ApiTokenInvalid,
 
-    #[error("Requested resource was not found")]
-    NotFound,
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
+
+    #[error("Environment variable {0} is not set")]
+    MissingEnvVar(String),
+
+    /// The requested resource doesn't exist.
+    ///
+    /// `resource` describes what wasn't found. For 404 responses, this is the path of the
+    /// request, like `/api/experimental/community/valheim/`, which tells a missing community
+    /// apart from a missing package.
+    #[error("Requested resource was not found{}", fmt_resource(.resource))]
+    NotFound { resource: Option<String> },
+
+    /// The server responded with a 4xx status other than 401 or 404, which have their own
+    /// variants. `body` usually explains what was wrong with the request.
+    #[error("API returned {status}: {body}")]
+    Client {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+
+    /// The server responded with a 5xx status.
+    #[error("Server error {status}: {body}")]
+    Server {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+
+    /// The server rejected a package submission, see [`Client::submit_package`](crate::Client::submit_package).
+    #[error("Package submission was rejected: {0}")]
+    Submission(crate::models::SubmissionError),
+
+    /// A response body was larger than the limit set with
+    /// [`ClientBuilder::with_max_response_size`](crate::ClientBuilder::with_max_response_size).
+    #[error("Response exceeded the maximum size of {limit} bytes")]
+    ResponseTooLarge { limit: usize },
 
     #[error("Invalid package ID")]
     InvalidPackageId,
+
+    #[error("Invalid community slug: {0}")]
+    InvalidCommunitySlug(String),
+
+    /// A method which uses the default community was called, but none was set with
+    /// [`ClientBuilder::with_default_community`](crate::ClientBuilder::with_default_community).
+    #[error("No default community is set")]
+    NoCommunitySet,
+}
+
+fn fmt_resource(resource: &Option<String>) -> String {
+    match resource {
+        Some(resource) => format!(": {}", resource),
+        None => String::new(),
+    }
+}
+
+impl Error {
+    /// Whether the error is likely to be temporary, such as a dropped connection or a
+    /// server error, so that sending the request again may succeed.
+    ///
+    /// Errors which would happen again, like a body that can't be decoded or an invalid URL,
+    /// are not transient. A [`Decode`](Error::Decode) error only is if the connection was lost
+    /// while reading the body.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Connect(_) | Error::Timeout(_) | Error::Server { .. } => true,
+            Error::Reqwest(err) => err.is_body(),
+            Error::Decode(err) => is_connection_lost(err),
+            _ => false,
+        }
+    }
+}
+
+/// Whether the error was caused by the connection closing or resetting.
+fn is_connection_lost(err: &reqwest::Error) -> bool {
+    use std::io::ErrorKind;
+
+    let mut source = std::error::Error::source(err);
+    while let Some(inner) = source {
+        if let Some(err) = inner.downcast_ref::<std::io::Error>() {
+            return matches!(
+                err.kind(),
+                ErrorKind::UnexpectedEof
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+            );
+        }
+        source = inner.source();
+    }
+
+    false
+}
+
+/// Whether the error was caused by the TLS handshake failing.
+fn is_tls_failure(err: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(err);
+    while let Some(inner) = source {
+        if inner.is::<native_tls::Error>() {
+            return true;
+        }
+        source = inner.source();
+    }
+
+    false
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        // the size limit is enforced inside the response body, so it comes back wrapped
+        let mut source = std::error::Error::source(&err);
+        while let Some(inner) = source {
+            if let Some(Error::ResponseTooLarge { limit }) = inner.downcast_ref::<Error>() {
+                return Error::ResponseTooLarge { limit: *limit };
+            }
+            source = inner.source();
+        }
+
+        if err.is_timeout() {
+            Error::Timeout(err)
+        } else if err.is_connect() && is_tls_failure(&err) {
+            Error::Tls(err)
+        } else if err.is_connect() {
+            Error::Connect(err)
+        } else if err.is_decode() {
+            Error::Decode(err)
+        } else {
+            Error::Reqwest(err)
+        }
+    }
 }
 
 /// A [`Result`] alias where the error type is [`crate::Error`].
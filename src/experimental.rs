@@ -1,33 +1,145 @@
 use crate::{
-    models::*, usermedia::PackageMetadata, Client, Error, IntoPackageId, IntoVersionId,
-    ResponseExt, Result,
+    models::*,
+    usermedia::PackageMetadata,
+    util::{json_stream, Boundary},
+    Client, Error, IntoPackageId, IntoVersionId, PackageId, ResponseJsonExt, Result, VersionId,
+    BATCH_CONCURRENCY, MAX_STREAM_RETRIES, RETRY_DELAY,
 };
+use async_stream::{stream, try_stream};
 use base64::{prelude::BASE64_STANDARD, Engine};
-use reqwest::Method;
-use std::{fmt::Display, path::Path};
-use tokio::fs;
+use futures_core::Stream;
+use futures_util::{pin_mut, stream, StreamExt, TryStreamExt};
+use reqwest::{
+    header::{ETAG, IF_NONE_MATCH},
+    Method, StatusCode,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    future::Future,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{fs, sync::Mutex};
 use uuid::Uuid;
 
 const PROFILE_DATA_PREFIX: &str = "#r2modman\n";
 
+/// An in-memory cache of the package index, see [`Client::get_package_index_cached`].
+#[derive(Debug)]
+pub(crate) struct IndexCache {
+    ttl: Duration,
+    entry: Mutex<Option<(Instant, Arc<Vec<PackageIndexEntry>>)>>,
+}
+
+impl IndexCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: Mutex::new(None),
+        }
+    }
+}
+
+/// A cache of packages and their ETags, see [`Client::get_package_cached`].
+#[derive(Debug, Default)]
+pub(crate) struct PackageCache {
+    pub(crate) entries: std::sync::Mutex<HashMap<PackageId, (String, Package)>>,
+}
+
 impl Client {
     /// Fetches a list of all packages on Thunderstore.
     pub async fn get_package_index(&self) -> Result<Vec<PackageIndexEntry>> {
+        self.stream_package_index().await?.try_collect().await
+    }
+
+    /// Asynchronously streams the entries of the package index as they are downloaded,
+    /// without buffering the whole index in memory.
+    pub async fn stream_package_index(
+        &self,
+    ) -> Result<impl Stream<Item = Result<PackageIndexEntry>>> {
         let url = self.experimental_url("package-index");
+        let response = self.send(self.client.get(&url)).await?;
+        let bytes = response.bytes_stream().map_err(Error::from);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .error_for_status()?
-            .text()
-            .await?;
+        Ok(json_stream(bytes, Boundary::Lines, |line| {
+            Ok(Some(serde_json::from_slice(line)?))
+        }))
+    }
 
-        response
-            .lines()
-            .map(|line| serde_json::from_str(line).map_err(Error::Json))
-            .collect()
+    /// Like [`Client::stream_package_index`], but reconnects if the download fails partway through.
+    ///
+    /// When a [transient](Error::is_transient) error occurs, the index is requested again and
+    /// the entries which were already yielded are skipped, so each entry is only yielded once.
+    /// This assumes the index doesn't change between attempts. The stream gives up and yields
+    /// the error after [`MAX_STREAM_RETRIES`] attempts in a row fail without yielding any new entries.
+    pub fn stream_package_index_resilient(
+        &self,
+    ) -> impl Stream<Item = Result<PackageIndexEntry>> + '_ {
+        stream! {
+            let mut yielded = 0;
+            let mut failures = 0;
+
+            'request: loop {
+                if failures > 0 {
+                    tokio::time::sleep(RETRY_DELAY * failures).await;
+                }
+
+                let error = match self.stream_package_index().await {
+                    Ok(entries) => {
+                        pin_mut!(entries);
+                        let mut skip = yielded;
+
+                        loop {
+                            match entries.next().await {
+                                Some(Ok(_)) if skip > 0 => skip -= 1,
+                                Some(Ok(entry)) => {
+                                    yielded += 1;
+                                    failures = 0;
+                                    yield Ok(entry);
+                                }
+                                Some(Err(err)) => break err,
+                                None => break 'request,
+                            }
+                        }
+                    }
+                    Err(err) => err,
+                };
+
+                if !error.is_transient() || failures >= MAX_STREAM_RETRIES {
+                    yield Err(error);
+                    break;
+                }
+
+                failures += 1;
+            }
+        }
+    }
+
+    /// Returns the package index, fetching it only if the cached copy is missing or expired.
+    ///
+    /// Caching is enabled with [`ClientBuilder::with_index_cache`](crate::ClientBuilder::with_index_cache).
+    /// The cache is shared between a [`Client`] and its clones, but not with separately built clients.
+    /// Without a cache, this fetches the index on every call like [`Client::get_package_index`].
+    pub async fn get_package_index_cached(&self) -> Result<Arc<Vec<PackageIndexEntry>>> {
+        let Some(cache) = &self.index_cache else {
+            return Ok(Arc::new(self.get_package_index().await?));
+        };
+
+        // hold the lock while fetching so concurrent callers don't fetch it twice
+        let mut entry = cache.entry.lock().await;
+
+        if let Some((fetched_at, index)) = entry.as_ref() {
+            if fetched_at.elapsed() < cache.ttl {
+                return Ok(index.clone());
+            }
+        }
+
+        let index = Arc::new(self.get_package_index().await?);
+        *entry = Some((Instant::now(), index.clone()));
+
+        Ok(index)
     }
 
     /// Fetches information about a single package.
@@ -44,10 +156,146 @@ impl Client {
     /// ```
     pub async fn get_package(&self, id: impl IntoPackageId) -> Result<Package> {
         let url = self.experimental_url(format_args!("package/{}", id.into_id()?.path()));
-        let response = self.client.get(&url).send().await.handle()?.json().await?;
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
         Ok(response)
     }
 
+    /// Fetches information about a single package, reusing the previous response if it hasn't changed.
+    ///
+    /// Caching is enabled with [`ClientBuilder::with_package_cache`](crate::ClientBuilder::with_package_cache).
+    /// The client then stores the `ETag` of each package and sends it with the next request
+    /// for the same package. If the server responds with `304 Not Modified`, the stored package
+    /// is returned instead of downloading it again. Without a cache, this is the same as
+    /// [`Client::get_package`].
+    ///
+    /// The cache is shared between a [`Client`] and its clones, but not with separately built clients.
+    ///
+    /// Returns [`Error::UnexpectedNotModified`] if the server responds with `304 Not Modified`
+    /// even though the package isn't cached.
+    pub async fn get_package_cached(&self, id: impl IntoPackageId) -> Result<Package> {
+        let id = id.into_id()?;
+
+        let Some(cache) = &self.package_cache else {
+            return self.get_package(id).await;
+        };
+
+        let cached = cache.entries.lock().unwrap().get(&id).cloned();
+
+        let url = self.experimental_url(format_args!("package/{}", id.path()));
+        let mut request = self.client.get(&url);
+        if let Some((etag, _)) = &cached {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = self.send(request).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            // the body is empty, so there's nothing to parse without a cached package
+            return cached
+                .map(|(_, package)| package)
+                .ok_or(Error::UnexpectedNotModified);
+        }
+
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        let package: Package = response.parse_json().await?;
+
+        if let Some(etag) = etag {
+            cache
+                .entries
+                .lock()
+                .unwrap()
+                .insert(id, (etag, package.clone()));
+        }
+
+        Ok(package)
+    }
+
+    /// Fetches information about many packages concurrently.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] requests are in flight at once, and the packages
+    /// are returned in the same order as `ids`. Fails if any of the packages can't be fetched;
+    /// use [`Client::get_packages_batch`] to get the result of each package instead.
+    pub async fn get_packages<I>(&self, ids: I) -> Result<Vec<Package>>
+    where
+        I: IntoIterator,
+        I::Item: IntoPackageId,
+    {
+        self.get_packages_batch(ids)
+            .await?
+            .into_iter()
+            .map(|(_, package)| package)
+            .collect()
+    }
+
+    /// Fetches information about many packages concurrently, keeping the result of each one.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] requests are in flight at once. The results are in the same
+    /// order as `ids`, and a failed request only affects the result of its own package.
+    /// Returns an error without sending any requests if one of the ids is invalid.
+    pub async fn get_packages_batch<I>(&self, ids: I) -> Result<Vec<(PackageId, Result<Package>)>>
+    where
+        I: IntoIterator,
+        I::Item: IntoPackageId,
+    {
+        let ids = ids
+            .into_iter()
+            .map(IntoPackageId::into_id)
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = stream::iter(ids)
+            .map(|id| async {
+                let package = self.get_package(id.clone()).await;
+                (id, package)
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok(results)
+    }
+
+    /// Fetches every package owned by a namespace (team), regardless of community.
+    ///
+    /// - `namespace` is the name of the team, compared case-insensitively.
+    ///
+    /// Unlike [`Client::list_packages_by_namespace`], this isn't limited to a single community,
+    /// but the API has no endpoint for a namespace's packages, so it's expensive: this downloads
+    /// the full package index (unless it's cached with
+    /// [`ClientBuilder::with_index_cache`](crate::ClientBuilder::with_index_cache)) to find the
+    /// namespace's packages, and then makes one more request per package with
+    /// [`Client::get_packages`]. The index lists each version separately, so the packages are
+    /// de-duplicated first.
+    ///
+    /// Returns [`Error::NotFound`] if the namespace doesn't exist or has no packages.
+    pub async fn list_all_packages_by_namespace(
+        &self,
+        namespace: impl AsRef<str>,
+    ) -> Result<Vec<Package>> {
+        let namespace = namespace.as_ref();
+        let index = self.get_package_index_cached().await?;
+
+        let mut seen = HashSet::new();
+        let ids: Vec<PackageId> = index
+            .iter()
+            .filter(|entry| entry.namespace.eq_ignore_ascii_case(namespace))
+            .map(|entry| PackageId::new(&entry.namespace, &entry.name))
+            .filter(|id| seen.insert(id.clone()))
+            .collect();
+
+        if ids.is_empty() {
+            return Err(Error::NotFound {
+                resource: Some(format!("packages in namespace {}", namespace)),
+            });
+        }
+
+        self.get_packages(ids).await
+    }
+
     /// Fetches information about a specific version of a package.
     ///
     /// ## Example
@@ -62,10 +310,99 @@ impl Client {
     /// ```
     pub async fn get_version(&self, id: impl IntoVersionId) -> Result<PackageVersion> {
         let url = self.experimental_url(format_args!("package/{}", id.into_id()?.path()));
-        let response = self.client.get(&url).send().await.handle()?.json().await?;
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
         Ok(response)
     }
 
+    /// Fetches the ids of every version of a package, sorted by version number, newest first.
+    ///
+    /// Neither the experimental nor the v1 API has an endpoint for a single package's versions
+    /// that doesn't require a community, so these are looked up in the package index. This is
+    /// expensive unless [`ClientBuilder::with_index_cache`](crate::ClientBuilder::with_index_cache)
+    /// is enabled.
+    ///
+    /// Returns [`Error::NotFound`] if the package isn't in the index.
+    pub async fn get_package_versions(
+        &self,
+        package: impl IntoPackageId,
+    ) -> Result<Vec<VersionId>> {
+        let package = package.into_id()?;
+        let index = self.get_package_index_cached().await?;
+
+        let mut versions: Vec<_> = index
+            .iter()
+            .filter(|entry| entry.namespace == package.namespace() && entry.name == package.name())
+            .map(|entry| &entry.version_number)
+            .collect();
+
+        if versions.is_empty() {
+            return Err(Error::NotFound {
+                resource: Some(package.into_string()),
+            });
+        }
+
+        versions.sort_by(|a, b| b.cmp(a));
+
+        Ok(versions
+            .into_iter()
+            .map(|version| VersionId::from((package.namespace(), package.name(), version)))
+            .collect())
+    }
+
+    /// Fetches the id of the latest version of a package.
+    ///
+    /// This is a shorthand for checking for updates. It goes through
+    /// [`Client::get_package_cached`], so repeated checks are cheap if
+    /// [`ClientBuilder::with_package_cache`](crate::ClientBuilder::with_package_cache) is enabled.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let latest = client.get_latest_version("Kesomannen-GaleModManager").await?;
+    /// println!("Latest version: {}", latest.version());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_latest_version(&self, id: impl IntoPackageId) -> Result<VersionId> {
+        let package = self.get_package_cached(id).await?;
+        Ok(VersionId::from(&package.latest))
+    }
+
+    /// Checks whether a package exists, without fetching its information.
+    pub async fn package_exists(&self, id: impl IntoPackageId) -> Result<bool> {
+        let url = self.experimental_url(format_args!("package/{}", id.into_id()?.path()));
+        self.exists(&url).await
+    }
+
+    /// Checks whether a specific version of a package exists, without fetching its information.
+    ///
+    /// This sends a `HEAD` request to the version endpoint, so neither the archive nor the
+    /// version's JSON is downloaded. Deprecating a package doesn't remove its versions, so this
+    /// only returns `false` for versions which were never published or have since been deleted.
+    pub async fn version_exists(&self, id: impl IntoVersionId) -> Result<bool> {
+        let url = self.experimental_url(format_args!("package/{}", id.into_id()?.path()));
+        self.exists(&url).await
+    }
+
+    async fn exists(&self, url: &str) -> Result<bool> {
+        let response = match self.send(self.client.head(url)).await {
+            // fall back to GET if a proxy or mirror doesn't allow HEAD
+            Err(Error::Client { status, .. }) if status == StatusCode::METHOD_NOT_ALLOWED => {
+                self.send(self.client.get(url)).await
+            }
+            response => response,
+        };
+
+        match response {
+            Ok(_) => Ok(true),
+            Err(Error::NotFound { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Fetches the changelog for a specific version of a package.
     /// The changelog is returned as a markdown string.
     ///
@@ -73,53 +410,145 @@ impl Client {
     pub async fn get_changelog(&self, id: impl IntoVersionId) -> Result<String> {
         let url = self.experimental_url(format_args!("package/{}/changelog", id.into_id()?.path()));
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .handle()?
-            .json::<MarkdownResponse>()
+            .send(self.client.get(&url))
+            .await?
+            .parse_json::<MarkdownResponse>()
             .await?;
 
         Ok(response.markdown)
     }
 
+    /// Fetches the changelog for a specific version of a package and splits it into a section per version.
+    ///
+    /// See [`ChangelogEntry::parse`] for how the sections are found. Like [`Client::get_changelog`],
+    /// returns [`Error::NotFound`] if the package doesn't have a changelog.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    /// let installed = semver::Version::new(0, 5, 0);
+    ///
+    /// let entries = client.get_changelog_sections("Kesomannen-GaleModManager-0.6.0").await?;
+    /// let missed = entries
+    ///     .iter()
+    ///     .filter(|entry| entry.version.as_ref().is_some_and(|version| *version > installed));
+    ///
+    /// for entry in missed {
+    ///     println!("{}", entry.body);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_changelog_sections(
+        &self,
+        id: impl IntoVersionId,
+    ) -> Result<Vec<ChangelogEntry>> {
+        let markdown = self.get_changelog(id).await?;
+        Ok(ChangelogEntry::parse(&markdown))
+    }
+
     /// Fetches the readme for a specific version of a package.
     /// The readme is returned as a markdown string.
     pub async fn get_readme(&self, id: impl IntoVersionId) -> Result<String> {
         let url = self.experimental_url(format_args!("package/{}/readme", id.into_id()?.path()));
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .handle()?
-            .json::<MarkdownResponse>()
+            .send(self.client.get(&url))
+            .await?
+            .parse_json::<MarkdownResponse>()
             .await?;
 
         Ok(response.markdown)
     }
 
+    /// Fetches the readmes of many versions concurrently, keeping the result of each one.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] requests are in flight at once. The results are in the same
+    /// order as `ids`, and a failed request, like a missing readme, only affects its own version.
+    /// Returns an error without sending any requests if one of the ids is invalid.
+    pub async fn prefetch_readmes<I>(&self, ids: I) -> Result<Vec<(VersionId, Result<String>)>>
+    where
+        I: IntoIterator,
+        I::Item: IntoVersionId,
+    {
+        self.prefetch_markdown(ids, |id| self.get_readme(id)).await
+    }
+
+    /// Fetches the changelogs of many versions concurrently, keeping the result of each one.
+    ///
+    /// Works like [`Client::prefetch_readmes`]. Versions without a changelog get [`Error::NotFound`].
+    pub async fn prefetch_changelogs<I>(&self, ids: I) -> Result<Vec<(VersionId, Result<String>)>>
+    where
+        I: IntoIterator,
+        I::Item: IntoVersionId,
+    {
+        self.prefetch_markdown(ids, |id| self.get_changelog(id))
+            .await
+    }
+
+    async fn prefetch_markdown<I, F, Fut>(
+        &self,
+        ids: I,
+        fetch: F,
+    ) -> Result<Vec<(VersionId, Result<String>)>>
+    where
+        I: IntoIterator,
+        I::Item: IntoVersionId,
+        F: Fn(VersionId) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let ids = ids
+            .into_iter()
+            .map(IntoVersionId::into_id)
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = stream::iter(ids)
+            .map(|id| {
+                let markdown = fetch(id.clone());
+                async move { (id, markdown.await) }
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok(results)
+    }
+
     /// Renders a markdown string to HTML.
     pub async fn render_markdown(&self, markdown: impl ToString) -> Result<String> {
         let url = self.experimental_url("frontend/render-markdown");
         let response = self
-            .client
-            .post(&url)
-            .json(&RenderMarkdownParams {
+            .send(self.client.post(&url).json(&RenderMarkdownParams {
                 markdown: markdown.to_string(),
-            })
-            .send()
+            }))
             .await?
-            .json::<RenderMarkdownResponse>()
+            .parse_json::<RenderMarkdownResponse>()
             .await?;
 
         Ok(response.html)
     }
 
+    /// Fetches the readme for a specific version of a package and renders it to HTML.
+    ///
+    /// This is a shorthand for [`Client::get_readme`] followed by [`Client::render_markdown`].
+    pub async fn get_readme_html(&self, id: impl IntoVersionId) -> Result<String> {
+        let markdown = self.get_readme(id).await?;
+        self.render_markdown(markdown).await
+    }
+
+    /// Fetches the changelog for a specific version of a package and renders it to HTML.
+    ///
+    /// This is a shorthand for [`Client::get_changelog`] followed by [`Client::render_markdown`].
+    /// Like [`Client::get_changelog`], returns [`Error::NotFound`] if the package doesn't have a changelog.
+    pub async fn get_changelog_html(&self, id: impl IntoVersionId) -> Result<String> {
+        let markdown = self.get_changelog(id).await?;
+        self.render_markdown(markdown).await
+    }
+
     /// Creates a profile with the given data and returns its key.
     ///
-    /// The data is expected to be a ZIP archive containing a `mods.yml` file and
+    /// The data is expected to be a ZIP archive containing an `export.r2x` file and
     /// any addition configuration files or directories. However, any arbitrary
     /// data is allowed, but will likely fail to import correctly in mod managers.
     ///
@@ -131,19 +560,25 @@ impl Client {
         let url = self.experimental_url("legacyprofile/create");
 
         let response = self
-            .client
-            .post(url)
-            .header("Content-Type", "application/octet-stream")
-            .body(base64)
-            .send()
+            .send(
+                self.client
+                    .post(url)
+                    .header("Content-Type", "application/octet-stream")
+                    .body(base64),
+            )
             .await?
-            .error_for_status()?
-            .json::<LegacyProfileCreateResponse>()
+            .parse_json::<LegacyProfileCreateResponse>()
             .await?;
 
         Ok(response.key)
     }
 
+    /// Creates a profile from a [`ProfileBuilder`] and returns its key.
+    #[cfg(feature = "zip")]
+    pub async fn create_profile_from_builder(&self, builder: ProfileBuilder) -> Result<Uuid> {
+        self.create_profile(builder.build()?).await
+    }
+
     /// Downloads a profile with the given key.
     ///
     /// The returned data is usually a ZIP archive containing a mods.yml file and
@@ -152,7 +587,7 @@ impl Client {
     pub async fn get_profile(&self, key: Uuid) -> Result<Vec<u8>> {
         let url = self.experimental_url(format_args!("legacyprofile/get/{}", key));
 
-        let response = self.client.get(url).send().await.handle()?.text().await?;
+        let response = self.send(self.client.get(url)).await?.text().await?;
 
         match response.strip_prefix(PROFILE_DATA_PREFIX) {
             Some(data) => BASE64_STANDARD.decode(data).map_err(Error::Base64),
@@ -160,6 +595,16 @@ impl Client {
         }
     }
 
+    /// Fetches a profile and parses the mod list from its `export.r2x` file.
+    ///
+    /// Use [`Client::get_profile`] to get the raw archive instead, for example to read
+    /// the config files it contains.
+    #[cfg(feature = "zip")]
+    pub async fn get_profile_manifest(&self, key: Uuid) -> Result<ProfileManifest> {
+        let data = self.get_profile(key).await?;
+        ProfileManifest::from_zip_bytes(&data)
+    }
+
     /// Downloads a profile with the given key and saves it to a file.
     ///
     /// The resulting file is usually a ZIP archive containing a mods.yml file and
@@ -175,8 +620,14 @@ impl Client {
     ///
     /// The contents must already have been uploaded by calling [`Client::initiate_upload`],
     /// streaming the data to the returned URLs, and finally calling [`Client::finish_upload`].
+    /// Only the UUID of the upload is needed, so the upload may have happened in another process.
+    /// To check that the upload is complete first, use [`Client::submit_existing`].
     ///
     /// This method requires a valid API token on the client.
+    ///
+    /// If the server rejects the submission, for example because of an invalid
+    /// community or category, an [`Error::Submission`] is returned with the
+    /// error messages for each field.
     pub async fn submit_package(
         &self,
         upload_uuid: Uuid,
@@ -185,19 +636,559 @@ impl Client {
         let url = self.experimental_url("submission/submit");
         metadata.upload_uuid = Some(upload_uuid);
 
-        let response = self
-            .auth_request(Method::POST, url)?
-            .json(&metadata)
-            .send()
+        let response = match self
+            .send(self.auth_request(Method::POST, url)?.json(&metadata))
             .await
-            .handle()?
-            .json()
-            .await?;
+        {
+            Ok(response) => response,
+            Err(Error::Client { status, body }) if status == StatusCode::BAD_REQUEST => {
+                return Err(Error::Submission(SubmissionError::from_error_body(&body)));
+            }
+            Err(err) => return Err(err),
+        };
+
+        response.parse_json().await
+    }
+
+    /// Submits a package which was uploaded earlier, possibly by another process.
+    ///
+    /// Like [`Client::submit_package`], but first fetches the upload with [`Client::get_usermedia`]
+    /// and returns [`Error::UploadNotComplete`] unless its status is
+    /// [`UserMediaStatus::UploadComplete`], instead of leaving it to the server to reject.
+    ///
+    /// This method requires a valid API token on the client.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use thunderstore::usermedia::PackageMetadata;
+    ///
+    /// # async fn run(uuid: uuid::Uuid) -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::builder().with_token("tss_XXX").build()?;
+    ///
+    /// // `uuid` was persisted after the upload was finished
+    /// let metadata = PackageMetadata::new("Kesomannen", ["lethal-company"]);
+    /// client.submit_existing(uuid, metadata).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn submit_existing(
+        &self,
+        upload_uuid: Uuid,
+        metadata: PackageMetadata,
+    ) -> Result<PackageSubmissionResult> {
+        let usermedia = self.get_usermedia(upload_uuid).await?;
+
+        if usermedia.status != UserMediaStatus::UploadComplete {
+            return Err(Error::UploadNotComplete {
+                status: usermedia.status,
+            });
+        }
 
+        self.submit_package(upload_uuid, metadata).await
+    }
+
+    /// Searches for packages in a community.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    /// - `query` is matched against the packages' names and descriptions.
+    /// - `cursor` is used to fetch another page of results. Pass `None` to get the first page.
+    ///
+    /// Returns the packages on the requested page, together with the cursors to the next
+    /// and previous pages.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use thunderstore::models::PackageOrdering;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let page = client
+    ///     .search_packages("lethal-company", "emotes", PackageOrdering::MostDownloaded, None)
+    ///     .await?;
+    ///
+    /// if let Some(next) = page.cursor.next {
+    ///     let more = client
+    ///         .search_packages("lethal-company", "emotes", PackageOrdering::MostDownloaded, Some(&next))
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_packages(
+        &self,
+        community: impl Display,
+        query: impl AsRef<str>,
+        ordering: PackageOrdering,
+        cursor: Option<&str>,
+    ) -> Result<Page<Package>> {
+        let url = self.experimental_url(format_args!("community/{}/package", community));
+        let query = [("q", query.as_ref()), ("ordering", ordering.as_str())];
+        self.get_page(&url, &query, cursor).await
+    }
+
+    /// Asynchronously streams the results of a package search, fetching pages as they are needed.
+    ///
+    /// The arguments are the same as for [`Client::search_packages`]. The stream ends
+    /// after the last page of results.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use futures_util::{pin_mut, TryStreamExt};
+    /// use thunderstore::models::PackageOrdering;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let stream = client.stream_search("lethal-company", "emotes", PackageOrdering::MostDownloaded);
+    /// pin_mut!(stream);
+    ///
+    /// while let Some(package) = stream.try_next().await? {
+    ///     println!("{}", package.full_name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_search(
+        &self,
+        community: impl Display,
+        query: impl AsRef<str>,
+        ordering: PackageOrdering,
+    ) -> impl Stream<Item = Result<Package>> + '_ {
+        let community = community.to_string();
+        let query = query.as_ref().to_owned();
+
+        paginate(move |cursor| {
+            let community = community.clone();
+            let query = query.clone();
+            async move {
+                self.search_packages(community, query, ordering, cursor.as_deref())
+                    .await
+            }
+        })
+    }
+
+    /// Checks whether an icon is valid for a package submission.
+    ///
+    /// - `namespace` is the team the package would be published under.
+    /// - `data` is the contents of the `icon.png` file.
+    ///
+    /// Returns a [`ValidationOutcome`] with the reasons the icon was rejected, if any.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn validate_icon(
+        &self,
+        namespace: impl Into<String>,
+        data: impl AsRef<[u8]>,
+    ) -> Result<ValidationOutcome> {
+        self.validate(
+            "icon",
+            &IconValidatorParams {
+                namespace: namespace.into(),
+                icon_data: BASE64_STANDARD.encode(data),
+            },
+        )
+        .await
+    }
+
+    /// Checks whether a manifest is valid for a package submission.
+    ///
+    /// - `namespace` is the team the package would be published under.
+    /// - `data` is the contents of the `manifest.json` file.
+    ///
+    /// Returns a [`ValidationOutcome`] with the reasons the manifest was rejected, if any.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn validate_manifest_v1(
+        &self,
+        namespace: impl Into<String>,
+        data: impl AsRef<[u8]>,
+    ) -> Result<ValidationOutcome> {
+        self.validate(
+            "manifest-v1",
+            &ManifestV1ValidatorParams {
+                namespace: namespace.into(),
+                manifest_data: BASE64_STANDARD.encode(data),
+            },
+        )
+        .await
+    }
+
+    /// Checks whether a readme is valid for a package submission.
+    ///
+    /// - `data` is the contents of the `README.md` file.
+    ///
+    /// Returns a [`ValidationOutcome`] with the reasons the readme was rejected, if any.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn validate_readme(&self, data: impl AsRef<[u8]>) -> Result<ValidationOutcome> {
+        self.validate(
+            "readme",
+            &ReadmeValidatorParams {
+                readme_data: BASE64_STANDARD.encode(data),
+            },
+        )
+        .await
+    }
+
+    /// Validates the manifest, readme and icon of a package concurrently.
+    ///
+    /// - `namespace` is the team the package would be published under.
+    /// - `manifest`, `readme` and `icon` are the contents of the package's
+    ///   `manifest.json`, `README.md` and `icon.png` files.
+    ///
+    /// Returns a [`PackageValidation`] with the outcome of each part. This is the same as
+    /// calling [`Client::validate_manifest_v1`], [`Client::validate_readme`] and
+    /// [`Client::validate_icon`], and fails if any of the requests fail.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn validate_package(
+        &self,
+        namespace: impl Into<String>,
+        manifest: impl AsRef<[u8]>,
+        readme: impl AsRef<[u8]>,
+        icon: impl AsRef<[u8]>,
+    ) -> Result<PackageValidation> {
+        let namespace = namespace.into();
+
+        let (manifest, readme, icon) = tokio::try_join!(
+            self.validate_manifest_v1(namespace.clone(), manifest),
+            self.validate_readme(readme),
+            self.validate_icon(namespace, icon),
+        )?;
+
+        Ok(PackageValidation {
+            manifest,
+            readme,
+            icon,
+        })
+    }
+
+    async fn validate(
+        &self,
+        validator: &str,
+        params: &impl serde::Serialize,
+    ) -> Result<ValidationOutcome> {
+        let url = self.experimental_url(format_args!("submission/validate/{}", validator));
+
+        let result = self
+            .send(self.auth_request(Method::POST, url)?.json(params))
+            .await;
+
+        match result {
+            Ok(response) => {
+                let response: ValidatorResponse = response.parse_json().await?;
+
+                Ok(ValidationOutcome {
+                    failed_without_reason: !response.success,
+                    ..Default::default()
+                })
+            }
+            // validation failures are reported as 400s with a map of field errors
+            Err(Error::Client { status, body }) if status == StatusCode::BAD_REQUEST => {
+                Ok(ValidationOutcome::from_error_body(&body))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetches a page of communities.
+    ///
+    /// - `cursor` is used to fetch another page of results. Pass `None` to get the first page.
+    ///
+    /// Returns the communities on the requested page, together with the cursors to the next
+    /// and previous pages.
+    ///
+    /// If you want to go through all communities, use [`Client::stream_communities`] instead.
+    pub async fn get_communities(&self, cursor: Option<&str>) -> Result<Page<Community>> {
+        let url = self.experimental_url("community");
+        self.get_page(&url, &[], cursor).await
+    }
+
+    /// Fetches the page of communities after the one `cursor` was returned with.
+    ///
+    /// Returns `None` if there is no next page.
+    pub async fn next_communities(&self, cursor: &CursorState) -> Result<Option<Page<Community>>> {
+        match &cursor.next {
+            Some(next) => self.get_communities(Some(next)).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the page of communities before the one `cursor` was returned with.
+    ///
+    /// Returns `None` if there is no previous page.
+    pub async fn prev_communities(&self, cursor: &CursorState) -> Result<Option<Page<Community>>> {
+        match &cursor.prev {
+            Some(prev) => self.get_communities(Some(prev)).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Asynchronously streams all communities, fetching pages as they are needed.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use futures_util::{pin_mut, TryStreamExt};
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let stream = client.stream_communities();
+    /// pin_mut!(stream);
+    ///
+    /// while let Some(community) = stream.try_next().await? {
+    ///     println!("{}", community.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_communities(&self) -> impl Stream<Item = Result<Community>> + '_ {
+        paginate(move |cursor| async move { self.get_communities(cursor.as_deref()).await })
+    }
+
+    /// Fetches information about a single community.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// Returns [`Error::NotFound`] if there is no community with the given slug.
+    pub async fn get_community(&self, community: impl AsRef<str>) -> Result<Community> {
+        let url = self.experimental_url(format_args!("community/{}", community.as_ref()));
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
         Ok(response)
     }
 
+    /// Fetches all communities and collects them in a `Vec`.
+    ///
+    /// The pages are fetched with [`Client::follow_pagination`].
+    /// If you want to process communities as they arrive, use [`Client::stream_communities`] instead.
+    pub async fn list_communities(&self) -> Result<Vec<Community>> {
+        let url = self.experimental_url("community");
+        let first = self.get_paginated(&url, &[], None).await?;
+        self.follow_pagination(first).await
+    }
+
+    /// Fetches a page of categories in a community.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    /// - `cursor` is used to fetch another page of results. Pass `None` to get the first page.
+    ///
+    /// Returns the categories on the requested page, together with the cursors to the next
+    /// and previous pages.
+    ///
+    /// If you want to go through all categories, use [`Client::stream_categories`] instead.
+    pub async fn get_categories(
+        &self,
+        community: impl AsRef<str>,
+        cursor: Option<&str>,
+    ) -> Result<Page<CommunityCategory>> {
+        let url = self.experimental_url(format_args!("community/{}/category", community.as_ref()));
+        self.get_page(&url, &[], cursor).await
+    }
+
+    /// Fetches the page of categories after the one `cursor` was returned with.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// Returns `None` if there is no next page.
+    pub async fn next_categories(
+        &self,
+        community: impl AsRef<str>,
+        cursor: &CursorState,
+    ) -> Result<Option<Page<CommunityCategory>>> {
+        match &cursor.next {
+            Some(next) => self.get_categories(community, Some(next)).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the page of categories before the one `cursor` was returned with.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// Returns `None` if there is no previous page.
+    pub async fn prev_categories(
+        &self,
+        community: impl AsRef<str>,
+        cursor: &CursorState,
+    ) -> Result<Option<Page<CommunityCategory>>> {
+        match &cursor.prev {
+            Some(prev) => self.get_categories(community, Some(prev)).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Asynchronously streams all categories in a community, fetching pages as they are needed.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    pub fn stream_categories(
+        &self,
+        community: impl AsRef<str>,
+    ) -> impl Stream<Item = Result<CommunityCategory>> + '_ {
+        let community = community.as_ref().to_owned();
+
+        paginate(move |cursor| {
+            let community = community.clone();
+            async move { self.get_categories(community, cursor.as_deref()).await }
+        })
+    }
+
+    /// Checks whether the client's API token is accepted by Thunderstore.
+    ///
+    /// Returns `Ok(false)` without sending a request if no token is set,
+    /// and `Ok(false)` if the server rejects or ignores the token.
+    pub async fn verify_token(&self) -> Result<bool> {
+        if self.token.is_none() {
+            return Ok(false);
+        }
+
+        let url = self.experimental_url("current-user");
+
+        let response: serde_json::Value =
+            match self.send(self.auth_request(Method::GET, url)?).await {
+                Ok(response) => response.parse_json().await?,
+                Err(Error::ApiTokenInvalid) => return Ok(false),
+                Err(err) => return Err(err),
+            };
+
+        // see `get_current_user`, an ignored token gets an anonymous user
+        Ok(!response["username"].is_null())
+    }
+
+    /// Fetches the user who owns the client's API token, including the teams they belong to.
+    ///
+    /// Returns [`Error::ApiTokenInvalid`] if the server doesn't recognize the token, and
+    /// [`Error::ApiTokenRequired`] without sending a request if no token is set.
+    /// Use [`Client::verify_token`] to only check the token.
+    pub async fn get_current_user(&self) -> Result<CurrentUser> {
+        let url = self.experimental_url("current-user");
+        let response: serde_json::Value = self
+            .send(self.auth_request(Method::GET, url)?)
+            .await?
+            .parse_json()
+            .await?;
+
+        // the endpoint responds with an anonymous user instead of 401 if the token is ignored
+        if response["username"].is_null() {
+            return Err(Error::ApiTokenInvalid);
+        }
+
+        Ok(serde_json::from_value(response)?)
+    }
+
+    /// Fetches all categories in a community and collects them in a `Vec`.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// The pages are fetched with [`Client::follow_pagination`].
+    /// If you want to process categories as they arrive, use [`Client::stream_categories`] instead.
+    pub async fn list_categories(
+        &self,
+        community: impl AsRef<str>,
+    ) -> Result<Vec<CommunityCategory>> {
+        let url = self.experimental_url(format_args!("community/{}/category", community.as_ref()));
+        let first = self.get_paginated(&url, &[], None).await?;
+        self.follow_pagination(first).await
+    }
+
+    /// Fetches the pages after `first` by following their [`Pagination::next_link`] until there
+    /// are no more pages, and collects the results of every page in order.
+    ///
+    /// The links are requested exactly as the server returned them, instead of extracting the
+    /// cursor like [`CursorState`] does, so this keeps working if the format of the links changes.
+    /// They are requested with the client's configuration, but without the API token.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let first: thunderstore::models::PaginatedResponse<thunderstore::models::Community> =
+    ///     serde_json::from_str(&std::fs::read_to_string("communities.json")?)?;
+    ///
+    /// let communities = client.follow_pagination(first).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn follow_pagination<T>(&self, first: PaginatedResponse<T>) -> Result<Vec<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut results = first.results;
+        let mut next = first.pagination.next_link;
+
+        while let Some(url) = next {
+            let response: PaginatedResponse<T> =
+                self.send(self.client.get(url)).await?.parse_json().await?;
+
+            results.extend(response.results);
+            next = response.pagination.next_link;
+        }
+
+        Ok(results)
+    }
+
+    async fn get_page<T>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        cursor: Option<&str>,
+    ) -> Result<Page<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let response = self.get_paginated(url, query, cursor).await?;
+        Ok(response.into())
+    }
+
+    async fn get_paginated<T>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        cursor: Option<&str>,
+    ) -> Result<PaginatedResponse<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut request = self.client.get(url).query(query);
+        if let Some(cursor) = cursor {
+            request = request.query(&[("cursor", cursor)]);
+        }
+
+        self.send(request).await?.parse_json().await
+    }
+
     pub(crate) fn experimental_url(&self, tail: impl Display) -> String {
         format!("{}/api/experimental/{}/", self.base_url, tail)
     }
 }
+
+/// Follows the cursors returned by `fetch` until there are no more pages,
+/// yielding the items of each page in order.
+pub(crate) fn paginate<T, F, Fut>(mut fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    try_stream! {
+        let mut cursor = None;
+
+        loop {
+            let page = fetch(cursor.take()).await?;
+
+            for item in page.items {
+                yield item;
+            }
+
+            match page.cursor.next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+    }
+}
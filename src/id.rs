@@ -6,9 +6,10 @@ use std::{
 };
 
 use crate::{
-    models::{Package, PackageVersion},
+    models::{Community, Package, PackageV1, PackageVersion, PackageVersionV1},
     Error, Result,
 };
+use url::Url;
 
 /// A unique identifier for a package version, often formatted as `namespace-name-version`
 /// and also known as a dependency string.
@@ -25,6 +26,9 @@ use crate::{
 ///
 /// Most methods on [`Client`] accept any type that implements [`IntoVersionId`],
 /// which allows any of the above methods to be used interchangeably.
+///
+/// The [`Ord`] implementation compares the underlying strings, so `1.10.0` sorts before `1.2.0`.
+/// Use [`VersionId::version_cmp`] or [`sort_versions`](crate::sort_versions) to order by version instead.
 #[derive(Eq, Clone)]
 pub struct VersionId {
     repr: String,
@@ -56,6 +60,55 @@ impl VersionId {
         &self.repr[self.version_start..]
     }
 
+    /// Parses the version component as a [`semver::Version`].
+    ///
+    /// Returns [`Error::InvalidSemver`] if the version is not valid semver, which is possible
+    /// since other constructors don't validate it. Use [`VersionId::parse_validated`] to
+    /// reject such ids up front.
+    pub fn parsed_version(&self) -> Result<semver::Version> {
+        Ok(self.version().parse()?)
+    }
+
+    /// Parses a version id and checks that its version component is valid semver.
+    ///
+    /// Unlike [`str::parse`], which only checks that the id has three components,
+    /// this rejects ids like `Author-Mod-latest`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use thunderstore::VersionId;
+    ///
+    /// assert!(VersionId::parse_validated("BepInEx-BepInExPack-5.4.2100").is_ok());
+    /// assert!(VersionId::parse_validated("BepInEx-BepInExPack-latest").is_err());
+    /// ```
+    pub fn parse_validated(s: &str) -> Result<Self> {
+        let id: Self = s.parse()?;
+        id.parsed_version()?;
+        Ok(id)
+    }
+
+    /// Parses a version from a Thunderstore package URL, such as
+    /// `https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/v/0.16.0/`.
+    ///
+    /// Returns [`Error::InvalidPackageId`] if the URL doesn't point to a specific version.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use thunderstore::VersionId;
+    ///
+    /// let url = "https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/v/0.16.0/".parse().unwrap();
+    /// let id = VersionId::from_url(&url).unwrap();
+    /// assert_eq!(id, VersionId::new("Evaisa", "LethalLib", "0.16.0"));
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        match package_url_segments(url)?[..] {
+            [namespace, name, "v", version] => Ok(Self::new(namespace, name, version)),
+            _ => Err(Error::InvalidPackageId),
+        }
+    }
+
     /// Returns an object that, when formatted with `{}`, will produce the URL path for this version.
     ///
     /// ## Example
@@ -68,6 +121,43 @@ impl VersionId {
         VersionIdPath::new(self)
     }
 
+    /// Compares two ids, ignoring the case of the namespace and name.
+    /// The version is still compared exactly.
+    ///
+    /// Note that the [`PartialEq`] implementation is case-sensitive.
+    pub fn eq_ignore_case(&self, other: &Self) -> bool {
+        self.namespace().eq_ignore_ascii_case(other.namespace())
+            && self.name().eq_ignore_ascii_case(other.name())
+            && self.version() == other.version()
+    }
+
+    /// Compares two ids by namespace, then name, then parsed [`semver::Version`].
+    ///
+    /// Unlike the [`Ord`] implementation, this orders `1.2.0` before `1.10.0`.
+    /// If either version isn't valid semver, the versions are compared as strings.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use thunderstore::VersionId;
+    ///
+    /// let a = VersionId::new("BepInEx", "BepInExPack", "5.4.2");
+    /// let b = VersionId::new("BepInEx", "BepInExPack", "5.4.10");
+    ///
+    /// assert_eq!(a.version_cmp(&b), Ordering::Less);
+    /// assert_eq!(a.cmp(&b), Ordering::Greater);
+    /// ```
+    pub fn version_cmp(&self, other: &Self) -> cmp::Ordering {
+        self.namespace()
+            .cmp(other.namespace())
+            .then_with(|| self.name().cmp(other.name()))
+            .then_with(|| match (self.parsed_version(), other.parsed_version()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => self.version().cmp(other.version()),
+            })
+    }
+
     /// Consumes the [`VersionId`] and returns the underlying string, formatted as `namespace-name-version`.
     pub fn into_string(self) -> String {
         self.repr
@@ -79,6 +169,28 @@ impl VersionId {
     }
 }
 
+/// Sorts version ids in ascending order with [`VersionId::version_cmp`],
+/// so the newest version of each package comes last.
+///
+/// ## Example
+///
+/// ```
+/// use thunderstore::VersionId;
+///
+/// let mut ids: Vec<VersionId> = ["A-Mod-1.10.0", "A-Mod-1.2.0", "A-Mod-1.9.1"]
+///     .iter()
+///     .map(|id| id.parse().unwrap())
+///     .collect();
+///
+/// thunderstore::sort_versions(&mut ids);
+///
+/// let versions: Vec<_> = ids.iter().map(VersionId::version).collect();
+/// assert_eq!(versions, ["1.2.0", "1.9.1", "1.10.0"]);
+/// ```
+pub fn sort_versions(ids: &mut [VersionId]) {
+    ids.sort_by(VersionId::version_cmp);
+}
+
 impl PartialEq for VersionId {
     fn eq(&self, other: &Self) -> bool {
         self.repr == other.repr
@@ -164,6 +276,12 @@ impl From<(&str, &str, &semver::Version)> for VersionId {
     }
 }
 
+impl From<(&PackageId, &semver::Version)> for VersionId {
+    fn from((package, version): (&PackageId, &semver::Version)) -> Self {
+        package.with_version(version)
+    }
+}
+
 impl From<&PackageVersion> for VersionId {
     fn from(pkg: &PackageVersion) -> Self {
         Self::new(&pkg.namespace, &pkg.name, &pkg.version_number.to_string())
@@ -176,6 +294,14 @@ impl From<&Package> for VersionId {
     }
 }
 
+impl From<&PackageVersionV1> for VersionId {
+    fn from(pkg: &PackageVersionV1) -> Self {
+        // v1 versions don't have a namespace field, but their full name starts with it
+        let namespace = pkg.full_name.split('-').next().unwrap_or_default();
+        Self::new(namespace, &pkg.name, &pkg.version_number.to_string())
+    }
+}
+
 struct VersionIdPath<'a> {
     id: &'a VersionId,
 }
@@ -232,6 +358,48 @@ impl PackageId {
         &self.repr[self.name_start..]
     }
 
+    /// Returns the id of a specific version of this package.
+    ///
+    /// `version` can be anything that formats as a version number, such as a `&str`
+    /// or a [`semver::Version`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use thunderstore::{PackageId, VersionId};
+    ///
+    /// let package = PackageId::new("BepInEx", "BepInExPack");
+    /// let version = semver::Version::new(5, 4, 2100);
+    ///
+    /// assert_eq!(package.with_version(&version), VersionId::new("BepInEx", "BepInExPack", "5.4.2100"));
+    /// assert_eq!(package.with_version("5.4.2100"), VersionId::from((&package, &version)));
+    /// ```
+    pub fn with_version(&self, version: impl Display) -> VersionId {
+        VersionId::new(self.namespace(), self.name(), &version.to_string())
+    }
+
+    /// Parses a package from a Thunderstore package URL, such as
+    /// `https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/`.
+    ///
+    /// URLs pointing to a specific version of the package are also accepted.
+    /// Returns [`Error::InvalidPackageId`] if the URL isn't a package URL.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use thunderstore::PackageId;
+    ///
+    /// let url = "https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/".parse().unwrap();
+    /// let id = PackageId::from_url(&url).unwrap();
+    /// assert_eq!(id, PackageId::new("Evaisa", "LethalLib"));
+    /// ```
+    pub fn from_url(url: &Url) -> Result<Self> {
+        match package_url_segments(url)?[..] {
+            [namespace, name] | [namespace, name, "v", _] => Ok(Self::new(namespace, name)),
+            _ => Err(Error::InvalidPackageId),
+        }
+    }
+
     /// Returns an object that, when formatted with `{}`, will produce the URL path for this package.
     ///
     /// ## Example
@@ -244,6 +412,13 @@ impl PackageId {
         PackageIdPath::new(self)
     }
 
+    /// Compares two ids, ignoring the case of the namespace and name.
+    ///
+    /// Note that the [`PartialEq`] implementation is case-sensitive.
+    pub fn eq_ignore_case(&self, other: &Self) -> bool {
+        self.repr.eq_ignore_ascii_case(&other.repr)
+    }
+
     /// Consumes the [`PackageId`] and returns the underlying string, formatted as `namespace-name`.
     pub fn into_string(self) -> String {
         self.repr
@@ -338,6 +513,12 @@ impl From<&Package> for PackageId {
     }
 }
 
+impl From<&PackageV1> for PackageId {
+    fn from(pkg: &PackageV1) -> Self {
+        Self::new(&pkg.owner, &pkg.name)
+    }
+}
+
 struct PackageIdPath<'a> {
     id: &'a PackageId,
 }
@@ -357,12 +538,26 @@ impl<'a> Display for PackageIdPath<'a> {
 impl From<&VersionId> for PackageId {
     fn from(id: &VersionId) -> Self {
         Self {
-            repr: id.repr[..id.version_start].to_string(),
+            repr: id.repr[..id.version_start - 1].to_string(),
             name_start: id.name_start,
         }
     }
 }
 
+/// Returns the path segments after `/p/` in a package URL, ignoring trailing slashes.
+fn package_url_segments(url: &Url) -> Result<Vec<&str>> {
+    let mut segments = url
+        .path_segments()
+        .ok_or(Error::InvalidPackageId)?
+        .filter(|segment| !segment.is_empty())
+        .skip_while(|segment| *segment != "p");
+
+    match segments.next() {
+        Some(_) => Ok(segments.collect()),
+        None => Err(Error::InvalidPackageId),
+    }
+}
+
 pub trait IntoVersionId {
     fn into_id(self) -> Result<VersionId>;
 }
@@ -412,3 +607,78 @@ impl IntoPackageId for &str {
         self.parse()
     }
 }
+
+/// The slug of a community, such as `lethal-company`.
+///
+/// Slugs are validated when parsed, so malformed ones are caught before making a request:
+/// ```
+/// use thunderstore::CommunitySlug;
+///
+/// let slug: CommunitySlug = "lethal-company".parse().unwrap();
+/// assert!("Lethal Company".parse::<CommunitySlug>().is_err());
+/// ```
+///
+/// Methods which take a community accept any type that implements [`Display`],
+/// so both slugs and raw strings can be used.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CommunitySlug(String);
+
+impl CommunitySlug {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for CommunitySlug {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<CommunitySlug> for String {
+    fn from(slug: CommunitySlug) -> Self {
+        slug.0
+    }
+}
+
+impl Display for CommunitySlug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for CommunitySlug {
+    type Error = Error;
+
+    /// Checks that the slug is non-empty kebab-case, containing only
+    /// lowercase letters, digits and single dashes between words.
+    fn try_from(value: String) -> Result<Self> {
+        let is_valid = !value.is_empty()
+            && value.split('-').all(|word| {
+                !word.is_empty()
+                    && word
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            });
+
+        if is_valid {
+            Ok(Self(value))
+        } else {
+            Err(Error::InvalidCommunitySlug(value))
+        }
+    }
+}
+
+impl FromStr for CommunitySlug {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.to_string().try_into()
+    }
+}
+
+impl From<&Community> for CommunitySlug {
+    fn from(community: &Community) -> Self {
+        Self(community.identifier.clone())
+    }
+}
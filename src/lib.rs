@@ -7,6 +7,17 @@
 //! Some methods, including uploading and submitting packages, require an API token to be set on the client.
 //! You can set this token using the [`ClientBuilder::with_token`] method.
 //!
+//! If you don't want to use async, enable the `blocking` feature and use `blocking::Client` instead.
+//!
+//! The `compression` feature, which is enabled by default, makes the client request gzip, brotli and
+//! deflate compressed responses. This greatly reduces the size of large responses like the package index.
+//!
+//! The `zip` feature adds methods which read package archives, like `Client::download_and_extract`
+//! and `PackageManifest::from_zip_bytes`.
+//!
+//! The `test-util` feature adds `Client::with_mock` and `transport::MockTransport`,
+//! which respond with canned bodies instead of using the network.
+//!
 //! # Examples
 //!
 //! ```no_run
@@ -29,18 +40,39 @@
 //! ```
 
 use bytes::Bytes;
-use std::{fmt::Debug, fs, path::Path};
+use experimental::{IndexCache, PackageCache};
+use futures_core::Stream;
+use futures_util::{pin_mut, StreamExt, TryStreamExt};
+use models::HasIcon;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH},
+    RequestBuilder, StatusCode,
+};
+use std::{
+    fmt::Debug,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::io::AsyncWriteExt;
+use transport::{SharedTransport, Transport};
 
 pub use error::{Error, Result};
-pub use id::{IntoPackageId, IntoVersionId, PackageId, VersionId};
+pub use id::{sort_versions, CommunitySlug, IntoPackageId, IntoVersionId, PackageId, VersionId};
 
 mod error;
 mod id;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cyberstorm;
+pub mod dependencies;
 pub mod experimental;
 pub mod models;
 pub mod schema;
+pub mod transport;
 pub mod usermedia;
+mod util;
 pub mod v1;
 pub mod wiki;
 
@@ -48,15 +80,39 @@ pub mod wiki;
 mod tests;
 
 const DEFAULT_BASE_URL: &str = "https://thunderstore.io";
+const DEFAULT_TOKEN_ENV_VAR: &str = "THUNDERSTORE_TOKEN";
+
+/// The maximum number of concurrent requests made by batch methods like [`Client::get_packages`].
+pub const BATCH_CONCURRENCY: usize = 8;
+
+/// The number of times [`Client::stream_package_index_resilient`] reconnects in a row
+/// without making progress before giving up.
+pub const MAX_STREAM_RETRIES: u32 = 3;
+
+/// How long to wait before the first reconnect, which is multiplied by the number of failed attempts.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
 
 /// A client for interacting with the Thunderstore API.
 ///
 /// The easiest way to create a client is to use the [`Client::new`] method.
 /// If you need more control over the client's configuration, use the [`Client::builder`] method instead.
+///
+/// Cloning a client is cheap, since the underlying connection pool is reference-counted
+/// and shared between clones. There's no need to wrap it in an `Arc` yourself.
+#[derive(Clone)]
 pub struct Client {
     base_url: String,
+    download_base_url: Option<String>,
     client: reqwest::Client,
     token: Option<String>,
+    request_hook: Option<RequestHook>,
+    metrics_hook: Option<MetricsHook>,
+    headers: HeaderMap,
+    max_response_size: Option<usize>,
+    transport: Option<SharedTransport>,
+    default_community: Option<CommunitySlug>,
+    index_cache: Option<Arc<IndexCache>>,
+    package_cache: Option<Arc<PackageCache>>,
 }
 
 impl Client {
@@ -82,30 +138,171 @@ impl Client {
         }
     }
 
+    /// Applies the request hook, if any, to a request which is about to be sent.
+    fn prepare(&self, request: RequestBuilder) -> RequestBuilder {
+        let request = request.headers(self.headers.clone());
+
+        match &self.request_hook {
+            Some(hook) => (hook.0)(request),
+            None => request,
+        }
+    }
+
+    /// Sends a request and converts error responses into [`Error`]s.
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let (client, request) = self.prepare(request).build_split();
+        let request = request?;
+        let path = request.url().path().to_owned();
+
+        let start = Instant::now();
+        let response = match &self.transport {
+            Some(transport) => transport.0.execute(request).await,
+            None => client.execute(request).await,
+        };
+
+        if let Some(hook) = &self.metrics_hook {
+            (hook.0)(
+                &path,
+                start.elapsed(),
+                response.as_ref().ok().map(|response| response.status()),
+            );
+        }
+
+        let response = response.handle(&path).await?;
+        self.limit_size(response)
+    }
+
+    /// Makes reading the body of a response fail with [`Error::ResponseTooLarge`]
+    /// once it exceeds the configured maximum size, if any.
+    fn limit_size(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        let Some(limit) = self.max_response_size else {
+            return Ok(response);
+        };
+
+        if response
+            .content_length()
+            .is_some_and(|len| len > limit as u64)
+        {
+            return Err(Error::ResponseTooLarge { limit });
+        }
+
+        let (parts, body) = http::Response::from(response).into_parts();
+
+        let mut read = 0;
+        let body = reqwest::Response::from(http::Response::new(body))
+            .bytes_stream()
+            .map(move |chunk| -> std::result::Result<Bytes, BoxError> {
+                let chunk = chunk?;
+                read += chunk.len();
+
+                if read > limit {
+                    Err(Box::new(Error::ResponseTooLarge { limit }))
+                } else {
+                    Ok(chunk)
+                }
+            });
+
+        Ok(http::Response::from_parts(parts, reqwest::Body::wrap_stream(body)).into())
+    }
+
+    /// Returns the community set with [`ClientBuilder::with_default_community`], if any.
+    pub fn default_community(&self) -> Option<&CommunitySlug> {
+        self.default_community.as_ref()
+    }
+
+    /// Returns the default community, or [`Error::NoCommunitySet`] if there isn't one.
+    fn require_community(&self) -> Result<&CommunitySlug> {
+        self.default_community.as_ref().ok_or(Error::NoCommunitySet)
+    }
+
+    /// Returns the URL which [`Client::download`] fetches a package's archive from, without making a request.
+    ///
+    /// This can be handed to an external downloader.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let client = thunderstore::Client::new();
+    ///
+    /// assert_eq!(
+    ///     client.download_url_for("BepInEx-BepInExPack-5.4.2100")?,
+    ///     "https://thunderstore.io/package/download/BepInEx/BepInExPack/5.4.2100/"
+    /// );
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    pub fn download_url_for(&self, version: impl IntoVersionId) -> Result<String> {
+        Ok(format!(
+            "{}/package/download/{}/",
+            self.download_base_url.as_ref().unwrap_or(&self.base_url),
+            version.into_id()?.path()
+        ))
+    }
+
     /// Downloads a package from Thunderstore.
     /// The resulting bytes represent a ZIP archive containing the contents of the package.
     ///
     /// If you want to save the package to a file, use the [`Client::download_to_file`] or
     /// [`Client::download_to_dir`] methods instead.
     pub async fn download(&self, version: impl IntoVersionId) -> Result<Bytes> {
-        let url = format!(
-            "{}/package/download/{}/",
-            self.base_url,
-            version.into_id()?.path()
-        );
-        let response = self.client.get(&url).send().await.handle()?.bytes().await?;
+        let url = self.download_url_for(version)?;
+        let response = self.send(self.client.get(&url)).await?.bytes().await?;
 
         Ok(response)
     }
 
+    /// Downloads a package as a stream of chunks, without buffering the whole archive in memory.
+    ///
+    /// Only the initial request is made before this returns, so errors can still occur
+    /// mid-stream, for example if the connection is dropped. In that case the stream yields
+    /// an error and the data received so far is incomplete.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use futures_util::TryStreamExt;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let mut stream = client.download_stream("Kesomannen-GaleModManager-0.6.0").await?;
+    /// let mut size = 0;
+    ///
+    /// while let Some(chunk) = stream.try_next().await? {
+    ///     size += chunk.len();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_stream(
+        &self,
+        version: impl IntoVersionId,
+    ) -> Result<impl Stream<Item = Result<Bytes>>> {
+        let url = self.download_url_for(version)?;
+        let response = self.send(self.client.get(&url)).await?;
+
+        Ok(response.bytes_stream().map_err(Error::from))
+    }
+
     /// Downloads a package and saves it to a file.
+    ///
+    /// The archive is streamed to the file as it's received, see [`Client::download_stream`].
     pub async fn download_to_file(
         &self,
         version: impl IntoVersionId,
         path: impl AsRef<Path>,
     ) -> Result<()> {
-        let data = self.download(version).await?;
-        fs::write(path, data).map_err(Error::Io)
+        let stream = self.download_stream(version).await?;
+        pin_mut!(stream);
+
+        let mut file = tokio::fs::File::create(path).await?;
+
+        while let Some(chunk) = stream.try_next().await? {
+            file.write_all(&chunk).await?;
+        }
+
+        file.flush().await?;
+
+        Ok(())
     }
 
     /// Downloads a package and saves it to a directory.
@@ -130,18 +327,104 @@ impl Client {
         dir: impl AsRef<Path>,
     ) -> Result<()> {
         let version = version.into_id()?;
+        let path = archive_path(dir.as_ref(), &version);
 
-        let mut path = dir.as_ref().join(version.as_str());
+        self.download_to_file(version, path).await
+    }
 
-        // the final version component might be treated as a file extension,
-        // so we can't do path.set_extension(), since that replaces the existing one
-        if let Some(ext) = path.extension() {
-            let mut new_ext = ext.to_os_string();
-            new_ext.push(".zip");
-            path.set_extension(new_ext);
+    /// Returns the size of a package's archive in bytes, without downloading it.
+    ///
+    /// This reads the `Content-Length` of a HEAD request to the download URL. If the server
+    /// doesn't allow HEAD requests or omits the header, the size is looked up in the package index
+    /// instead, which is expensive unless [`ClientBuilder::with_index_cache`] is enabled.
+    ///
+    /// Returns [`Error::UnknownFileSize`] if neither source yields a size.
+    pub async fn version_file_size(&self, version: impl IntoVersionId) -> Result<u64> {
+        let version = version.into_id()?;
+
+        if let Some(size) = self.content_length(&version).await? {
+            return Ok(size);
         }
 
-        self.download_to_file(version, path).await
+        let index = self.get_package_index_cached().await?;
+        index_file_size(&index, &version)
+    }
+
+    /// Reads the `Content-Length` of a HEAD request to a version's download URL,
+    /// or returns `None` if the server doesn't allow HEAD requests or omits the header.
+    pub(crate) async fn content_length(&self, version: &VersionId) -> Result<Option<u64>> {
+        let url = self.download_url_for(version.clone())?;
+
+        match self.send(self.client.head(&url)).await {
+            Ok(response) => Ok(response
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok()?.parse().ok())),
+            Err(Error::Client { status, .. }) if status == StatusCode::METHOD_NOT_ALLOWED => {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Downloads the contents of any URL, such as one of the [`Url`](url::Url) fields of the models.
+    ///
+    /// The request is made with the client's configuration, including its proxy and request hook,
+    /// but without the API token. The host is **not** checked against the base URL, since some
+    /// resources like icons are served from a CDN, so avoid passing URLs from untrusted sources.
+    pub async fn download_url(&self, url: &url::Url) -> Result<Bytes> {
+        let response = self
+            .send(self.client.get(url.as_str()))
+            .await?
+            .bytes()
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Downloads the icon of a package or package version.
+    /// The resulting bytes are usually a 256x256 PNG image.
+    ///
+    /// This works with any model that implements [`HasIcon`], including both
+    /// [`PackageVersion`](models::PackageVersion) and [`PackageVersionV1`](models::PackageVersionV1).
+    ///
+    /// Returns [`Error::NotFound`] if the model doesn't have an icon, like a [`PackageV1`](models::PackageV1)
+    /// without any versions.
+    pub async fn get_icon(&self, version: &impl HasIcon) -> Result<Bytes> {
+        let url = version.icon_url().ok_or(Error::NotFound {
+            resource: Some("icon".to_owned()),
+        })?;
+
+        let response = self.send(self.client.get(url)).await?.bytes().await?;
+
+        Ok(response)
+    }
+
+    /// Downloads a package and reads the `manifest.json` file from its archive.
+    ///
+    /// If you already have the package's data, use [`PackageManifest::from_zip_bytes`](models::PackageManifest::from_zip_bytes) instead.
+    #[cfg(feature = "zip")]
+    pub async fn get_manifest(
+        &self,
+        version: impl IntoVersionId,
+    ) -> Result<models::PackageManifest> {
+        let data = self.download(version).await?;
+        models::PackageManifest::from_zip_bytes(&data)
+    }
+
+    /// Downloads a package and extracts its archive into `dest_dir`.
+    ///
+    /// Returns the paths of the extracted files. Fails with [`Error::UnsafeArchivePath`]
+    /// before writing anything if an entry would end up outside of `dest_dir`,
+    /// for example by containing `..`.
+    #[cfg(feature = "zip")]
+    pub async fn download_and_extract(
+        &self,
+        version: impl IntoVersionId,
+        dest_dir: impl AsRef<Path>,
+    ) -> Result<Vec<std::path::PathBuf>> {
+        let data = self.download(version).await?;
+        extract_zip(&data, dest_dir.as_ref())
     }
 }
 
@@ -149,35 +432,204 @@ impl Default for Client {
     fn default() -> Self {
         Self {
             base_url: DEFAULT_BASE_URL.to_string(),
+            download_base_url: None,
             client: reqwest::Client::new(),
             token: None,
+            request_hook: None,
+            metrics_hook: None,
+            headers: HeaderMap::new(),
+            max_response_size: None,
+            transport: None,
+            default_community: None,
+            index_cache: None,
+            package_cache: None,
         }
     }
 }
 
+/// Returns the path of a version's archive in `dir`, formatted as `{dir}/{namespace}-{name}-{version}.zip`.
+fn archive_path(dir: &Path, version: &VersionId) -> std::path::PathBuf {
+    let mut path = dir.join(version.as_str());
+
+    // the final version component might be treated as a file extension,
+    // so we can't do path.set_extension(), since that replaces the existing one
+    if let Some(ext) = path.extension() {
+        let mut new_ext = ext.to_os_string();
+        new_ext.push(".zip");
+        path.set_extension(new_ext);
+    }
+
+    path
+}
+
+#[cfg(feature = "zip")]
+fn extract_zip(data: &[u8], dest_dir: &Path) -> Result<Vec<std::path::PathBuf>> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))?;
+
+    // check every path up front so a malicious archive doesn't leave half its files behind
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        if file.enclosed_name().is_none() {
+            return Err(Error::UnsafeArchivePath(file.name().to_owned()));
+        }
+    }
+
+    let mut paths = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let path = dest_dir.join(file.enclosed_name().expect("checked above"));
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::io::copy(&mut file, &mut std::fs::File::create(&path)?)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
 trait ResponseExt {
-    fn handle(self) -> Result<reqwest::Response>;
+    /// Converts error responses to a request for `path` into [`Error`]s.
+    async fn handle(self, path: &str) -> Result<reqwest::Response>;
 }
 
 impl ResponseExt for reqwest::Result<reqwest::Response> {
-    fn handle(self) -> Result<reqwest::Response> {
-        match self.and_then(|res| res.error_for_status()) {
-            Ok(res) => Ok(res),
-            Err(err) => match err.status() {
-                Some(reqwest::StatusCode::UNAUTHORIZED) => Err(Error::ApiTokenInvalid),
-                Some(reqwest::StatusCode::NOT_FOUND) => Err(Error::NotFound),
-                _ => Err(Error::Reqwest(err)),
-            },
+    async fn handle(self, path: &str) -> Result<reqwest::Response> {
+        let response = self?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED => Err(Error::ApiTokenInvalid),
+            StatusCode::NOT_FOUND => Err(Error::NotFound {
+                resource: Some(path.to_owned()),
+            }),
+            status if status.is_client_error() => {
+                // the body usually explains what was wrong with the request
+                let body = response.text().await?;
+                Err(Error::Client { status, body })
+            }
+            status if status.is_server_error() => {
+                let body = response.text().await?;
+                Err(Error::Server { status, body })
+            }
+            _ => Ok(response),
         }
     }
 }
 
+/// The maximum length of a response body stored in [`Error::Deserialize`].
+const MAX_ERROR_BODY_LEN: usize = 4096;
+
+trait ResponseJsonExt {
+    async fn parse_json<T: serde::de::DeserializeOwned>(self) -> Result<T>;
+}
+
+impl ResponseJsonExt for reqwest::Response {
+    /// Like [`reqwest::Response::json`], but keeps the body if it fails to deserialize.
+    async fn parse_json<T: serde::de::DeserializeOwned>(self) -> Result<T> {
+        let mut body = self.text().await?;
+
+        serde_json::from_str(&body).map_err(|source| {
+            if body.len() > MAX_ERROR_BODY_LEN {
+                let mut end = MAX_ERROR_BODY_LEN;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+                body.truncate(end);
+            }
+
+            Error::Deserialize { source, body }
+        })
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+type RequestHookFn = dyn Fn(RequestBuilder) -> RequestBuilder + Send + Sync;
+
+#[derive(Clone)]
+struct RequestHook(Arc<RequestHookFn>);
+
+impl Debug for RequestHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestHook")
+    }
+}
+
+type MetricsHookFn = dyn Fn(&str, Duration, Option<StatusCode>) + Send + Sync;
+
+#[derive(Clone)]
+struct MetricsHook(Arc<MetricsHookFn>);
+
+impl Debug for MetricsHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MetricsHook")
+    }
+}
+
 /// A builder for configuring a [`Client`] instance.
 #[derive(Debug, Default)]
 pub struct ClientBuilder {
     base_url: Option<String>,
+    download_base_url: Option<String>,
+    base_path: Option<String>,
     client: Option<reqwest::Client>,
-    token: Option<String>,
+    token: Option<TokenSource>,
+    request_hook: Option<RequestHook>,
+    metrics_hook: Option<MetricsHook>,
+    headers: HeaderMap,
+    extra_headers: Vec<(String, String)>,
+    max_response_size: Option<usize>,
+    transport: Option<SharedTransport>,
+    default_community: Option<String>,
+    index_cache_ttl: Option<Duration>,
+    package_cache: bool,
+    proxy: Option<ProxySource>,
+    root_certificates: Vec<reqwest::Certificate>,
+    accept_invalid_certs: bool,
+    #[cfg(feature = "compression")]
+    compression: Option<bool>,
+}
+
+#[derive(Debug)]
+enum ProxySource {
+    Proxy(Box<reqwest::Proxy>),
+    Url(String),
+}
+
+impl ProxySource {
+    fn resolve(self) -> Result<reqwest::Proxy> {
+        match self {
+            ProxySource::Proxy(proxy) => Ok(*proxy),
+            ProxySource::Url(url) => Ok(reqwest::Proxy::all(url)?),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TokenSource {
+    Value(String),
+    /// Environment variables to try in order.
+    Env(Vec<String>),
+}
+
+impl TokenSource {
+    fn resolve(self) -> Result<String> {
+        match self {
+            TokenSource::Value(token) => Ok(token),
+            TokenSource::Env(vars) => vars
+                .iter()
+                .find_map(|var| std::env::var(var).ok().filter(|token| !token.is_empty()))
+                .ok_or_else(|| Error::MissingEnvVar(vars.join(" or "))),
+        }
+    }
 }
 
 impl ClientBuilder {
@@ -192,6 +644,51 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the base URL which package archives are downloaded from, for deployments
+    /// which serve them from a different host than the API. Defaults to the base URL.
+    ///
+    /// This affects [`Client::download`] and the other methods which download archives,
+    /// as well as [`Client::download_url_for`]. The archive path, `/package/download/...`,
+    /// is appended as is, and the prefix from [`ClientBuilder::with_base_path`] isn't applied.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let client = thunderstore::Client::builder()
+    ///     .with_download_base_url("https://cdn.example.com")
+    ///     .build()?;
+    ///
+    /// assert_eq!(
+    ///     client.download_url_for("BepInEx-BepInExPack-5.4.2100")?,
+    ///     "https://cdn.example.com/package/download/BepInEx/BepInExPack/5.4.2100/"
+    /// );
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    pub fn with_download_base_url(mut self, url: impl Into<String>) -> Self {
+        self.download_base_url = Some(url.into());
+        self
+    }
+
+    /// Sets a path prefix which is inserted between the base URL and every request path,
+    /// for instances served from a subpath behind a reverse proxy.
+    ///
+    /// Leading and trailing slashes are ignored.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// // requests go to https://example.com/thunderstore/api/experimental/...
+    /// let client = thunderstore::Client::builder()
+    ///     .with_base_url("https://example.com")
+    ///     .with_base_path("/thunderstore/")
+    ///     .build()?;
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    pub fn with_base_path(mut self, prefix: impl Into<String>) -> Self {
+        self.base_path = Some(prefix.into());
+        self
+    }
+
     /// Sets the client to use Thunderstore's staging repository instead of the main one.
     ///
     /// Equivalent to calling `with_base_url("https://thunderstore.dev")`.
@@ -207,22 +704,283 @@ impl ClientBuilder {
         self
     }
 
+    /// Routes all requests through a proxy.
+    ///
+    /// This is ignored if a client is set with [`ClientBuilder::with_client`],
+    /// in which case the proxy should be configured on that client instead.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(ProxySource::Proxy(Box::new(proxy)));
+        self
+    }
+
+    /// Routes all requests through the proxy at `url`, for example `http://proxy.example.com:8080`.
+    ///
+    /// The URL is parsed when [`ClientBuilder::build`] is called, which fails if it's invalid.
+    /// Like [`ClientBuilder::with_proxy`], this is ignored if a client is set with [`ClientBuilder::with_client`].
+    pub fn with_proxy_url(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(ProxySource::Url(url.into()));
+        self
+    }
+
+    /// Trusts an additional root certificate, for example of a private CA
+    /// used by a self-hosted instance. Can be called multiple times.
+    ///
+    /// Like [`ClientBuilder::with_proxy`], this is ignored if a client is set with [`ClientBuilder::with_client`].
+    pub fn with_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Disables TLS certificate validation, accepting invalid and self-signed certificates.
+    ///
+    /// **This is dangerous** and makes requests vulnerable to man-in-the-middle attacks.
+    /// Only use it in test environments, and prefer [`ClientBuilder::with_root_certificate`] otherwise.
+    ///
+    /// Like [`ClientBuilder::with_proxy`], this is ignored if a client is set with [`ClientBuilder::with_client`].
+    pub fn with_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Sets whether to request compressed responses, which is enabled by default.
+    ///
+    /// Requires the `compression` feature, which enables the `gzip`, `brotli` and `deflate`
+    /// features of reqwest. Like [`ClientBuilder::with_proxy`], this is ignored if a client is
+    /// set with [`ClientBuilder::with_client`], in which case those features have to be enabled
+    /// and configured on that client instead.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Sets the maximum number of bytes to read from a response body. There's no limit by default.
+    ///
+    /// Reading a larger body fails with [`Error::ResponseTooLarge`]. The limit is checked
+    /// as the body is received, so a large response is never fully buffered. This also applies
+    /// to streaming methods like [`Client::stream_package_index`] and [`Client::download_stream`],
+    /// which will yield the error once the limit is reached.
+    pub fn with_max_response_size(mut self, limit: usize) -> Self {
+        self.max_response_size = Some(limit);
+        self
+    }
+
+    /// Sets a custom [`Transport`] to send requests with, instead of the underlying [`reqwest::Client`].
+    ///
+    /// The transport still receives requests built by the client, so options like custom headers
+    /// and the request hook apply, but connection settings like [`ClientBuilder::with_proxy`] don't.
+    pub fn with_transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(SharedTransport(Arc::new(transport)));
+        self
+    }
+
+    /// Sets the community used by methods like [`Client::list_packages`], which don't take one.
+    ///
+    /// Methods which take a community still use the one they're given. Building the client
+    /// fails with [`Error::InvalidCommunitySlug`] if `community` isn't a valid slug.
+    pub fn with_default_community(mut self, community: impl Into<String>) -> Self {
+        self.default_community = Some(community.into());
+        self
+    }
+
     /// Sets the API token to use for requests.
     ///
     /// This is required for some actions, such as uploading packages.
     pub fn with_token(mut self, token: impl Into<String>) -> Self {
-        self.token = Some(token.into());
+        self.token = Some(TokenSource::Value(token.into()));
+        self
+    }
+
+    /// Reads the API token from an environment variable.
+    ///
+    /// The variable is read when [`ClientBuilder::build`] is called, which returns
+    /// [`Error::MissingEnvVar`] if it's unset or empty.
+    pub fn with_token_from_env(mut self, var_name: impl Into<String>) -> Self {
+        self.token = Some(TokenSource::Env(vec![var_name.into()]));
+        self
+    }
+
+    /// Reads the API token from an environment variable, falling back to `THUNDERSTORE_TOKEN`.
+    ///
+    /// The variables are read when [`ClientBuilder::build`] is called, which returns
+    /// [`Error::MissingEnvVar`] if both are unset or empty.
+    pub fn with_token_from_env_or_default(mut self, var_name: impl Into<String>) -> Self {
+        self.token = Some(TokenSource::Env(vec![
+            var_name.into(),
+            DEFAULT_TOKEN_ENV_VAR.to_owned(),
+        ]));
+        self
+    }
+
+    /// Adds a header which is sent with every request, for example the key of an API gateway.
+    ///
+    /// The name and value are validated when [`ClientBuilder::build`] is called,
+    /// which returns [`Error::InvalidHeader`] if either is invalid. Headers added with this
+    /// method take precedence over ones with the same name from [`ClientBuilder::with_headers`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let client = thunderstore::Client::builder()
+    ///     .with_header("X-Api-Key", "secret")
+    ///     .build()?;
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds headers which are sent with every request. Can be called multiple times.
+    ///
+    /// See [`ClientBuilder::with_header`] to add a single header.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Sets a function which is called on every request just before it's sent.
+    ///
+    /// This can be used to add custom headers, or to integrate with tracing or logging.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let client = thunderstore::Client::builder()
+    ///     .with_request_hook(|request| request.header("X-Proxy-Auth", "secret"))
+    ///     .build()?;
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    pub fn with_request_hook(
+        mut self,
+        hook: impl Fn(RequestBuilder) -> RequestBuilder + Send + Sync + 'static,
+    ) -> Self {
+        self.request_hook = Some(RequestHook(Arc::new(hook)));
+        self
+    }
+
+    /// Sets a function which is called after every API request with the path of the URL,
+    /// the time it took to receive the response headers, and the status code.
+    ///
+    /// The status is `None` if the request failed without a response, for example
+    /// because the connection couldn't be established.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let client = thunderstore::Client::builder()
+    ///     .with_metrics_hook(|path, elapsed, status| {
+    ///         println!("{} took {:?} ({:?})", path, elapsed, status);
+    ///     })
+    ///     .build()?;
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    pub fn with_metrics_hook(
+        mut self,
+        hook: impl Fn(&str, Duration, Option<StatusCode>) + Send + Sync + 'static,
+    ) -> Self {
+        self.metrics_hook = Some(MetricsHook(Arc::new(hook)));
+        self
+    }
+
+    /// Enables an in-memory cache for [`Client::get_package_index_cached`].
+    ///
+    /// The cached index is reused until it's older than `ttl`, after which it's fetched again.
+    pub fn with_index_cache(mut self, ttl: Duration) -> Self {
+        self.index_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Enables ETag caching for [`Client::get_package_cached`].
+    pub fn with_package_cache(mut self) -> Self {
+        self.package_cache = true;
         self
     }
 
     /// Builds a client with the configured options.
     pub fn build(self) -> Result<Client> {
+        let mut headers = self.headers;
+
+        for (name, value) in self.extra_headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidHeader(name.clone()))?;
+            let header_value =
+                HeaderValue::from_str(&value).map_err(|_| Error::InvalidHeader(name))?;
+            headers.insert(header_name, header_value);
+        }
+
+        let mut base_url = self
+            .base_url
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+
+        if let Some(prefix) = self.base_path {
+            let prefix = prefix.trim_matches('/');
+            if !prefix.is_empty() {
+                base_url = format!("{}/{}", base_url.trim_end_matches('/'), prefix);
+            }
+        }
+
         Ok(Client {
-            base_url: self
-                .base_url
-                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
-            client: self.client.unwrap_or_default(),
-            token: self.token,
+            base_url,
+            download_base_url: self
+                .download_base_url
+                .map(|url| url.trim_end_matches('/').to_owned()),
+            client: match self.client {
+                Some(client) => client,
+                None => {
+                    let mut builder = reqwest::Client::builder()
+                        .danger_accept_invalid_certs(self.accept_invalid_certs);
+
+                    #[cfg(feature = "compression")]
+                    {
+                        let compression = self.compression.unwrap_or(true);
+                        builder = builder
+                            .gzip(compression)
+                            .brotli(compression)
+                            .deflate(compression);
+                    }
+
+                    if let Some(proxy) = self.proxy {
+                        builder = builder.proxy(proxy.resolve()?);
+                    }
+
+                    for certificate in self.root_certificates {
+                        builder = builder.add_root_certificate(certificate);
+                    }
+
+                    builder.build()?
+                }
+            },
+            token: self.token.map(TokenSource::resolve).transpose()?,
+            request_hook: self.request_hook,
+            metrics_hook: self.metrics_hook,
+            headers,
+            max_response_size: self.max_response_size,
+            transport: self.transport,
+            default_community: self
+                .default_community
+                .map(CommunitySlug::try_from)
+                .transpose()?,
+            index_cache: self
+                .index_cache_ttl
+                .map(|ttl| Arc::new(IndexCache::new(ttl))),
+            package_cache: self.package_cache.then(Default::default),
         })
     }
 }
+
+/// Looks up the size of a version's archive in the package index.
+pub(crate) fn index_file_size(
+    index: &[models::PackageIndexEntry],
+    version: &VersionId,
+) -> Result<u64> {
+    index
+        .iter()
+        .find(|entry| {
+            entry.namespace == version.namespace()
+                && entry.name == version.name()
+                && entry.version_number.to_string() == version.version()
+        })
+        .map(|entry| entry.file_size)
+        .ok_or(Error::UnknownFileSize)
+}
@@ -1,6 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, hash::Hash};
+use std::{
+    cmp::{self, Reverse},
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
 use url::Url;
 use uuid::Uuid;
 
@@ -23,10 +27,25 @@ pub struct PackageV1 {
 }
 
 impl PackageV1 {
+    /// Returns the first version in the list, which the API currently sorts newest first.
+    ///
+    /// Use [`PackageV1::newest_version`] to not rely on this order.
     pub fn latest(&self) -> &PackageVersionV1 {
         &self.versions[0]
     }
 
+    /// Returns the versions sorted by creation date, newest first.
+    pub fn versions_by_date(&self) -> Vec<&PackageVersionV1> {
+        let mut versions: Vec<_> = self.versions.iter().collect();
+        versions.sort_by_key(|v| Reverse(v.date_created));
+        versions
+    }
+
+    /// Returns the most recently created version, regardless of the order of [`PackageV1::versions`].
+    pub fn newest_version(&self) -> Option<&PackageVersionV1> {
+        self.versions.iter().max_by_key(|v| v.date_created)
+    }
+
     pub fn is_modpack(&self) -> bool {
         self.categories.contains("Modpacks")
     }
@@ -39,6 +58,21 @@ impl PackageV1 {
         self.versions.iter().find(|v| v.version_number == *version)
     }
 
+    /// Returns the version with the given id, if it belongs to this package.
+    ///
+    /// The id is compared case-sensitively, like [`VersionId`](crate::VersionId)'s [`PartialEq`] implementation.
+    pub fn get_version_with_id(&self, id: &crate::VersionId) -> Option<&PackageVersionV1> {
+        self.versions.iter().find(|v| v.full_name == id.as_str())
+    }
+
+    /// Returns the highest version which satisfies the given requirement, if any.
+    pub fn matching_version(&self, req: &semver::VersionReq) -> Option<&PackageVersionV1> {
+        self.versions
+            .iter()
+            .filter(|v| req.matches(&v.version_number))
+            .max_by(|a, b| a.version_number.cmp(&b.version_number))
+    }
+
     pub fn total_downloads(&self) -> u32 {
         self.versions.iter().map(|v| v.downloads).sum()
     }
@@ -85,6 +119,39 @@ impl Hash for PackageVersionV1 {
     }
 }
 
+/// A model which has an icon, which can be downloaded with [`Client::get_icon`].
+///
+/// [`Client::get_icon`]: crate::Client::get_icon
+pub trait HasIcon {
+    /// Returns the URL of the icon, or `None` if the model doesn't have one.
+    fn icon_url(&self) -> Option<&str>;
+}
+
+impl HasIcon for PackageVersionV1 {
+    fn icon_url(&self) -> Option<&str> {
+        Some(&self.icon)
+    }
+}
+
+impl HasIcon for PackageV1 {
+    /// The icon of the first version, or `None` if the package has no versions.
+    fn icon_url(&self) -> Option<&str> {
+        self.versions.first()?.icon_url()
+    }
+}
+
+impl HasIcon for PackageVersion {
+    fn icon_url(&self) -> Option<&str> {
+        Some(self.icon.as_str())
+    }
+}
+
+impl HasIcon for Package {
+    fn icon_url(&self) -> Option<&str> {
+        self.latest.icon_url()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LegacyProfileCreateResponse {
     pub key: Uuid,
@@ -103,6 +170,111 @@ pub struct PackageManifest {
     pub installers: Option<Vec<PackageInstaller>>,
 }
 
+impl PackageManifest {
+    /// Reads the `manifest.json` file at the root of a package's ZIP archive.
+    ///
+    /// Returns [`Error::ManifestNotFound`](crate::Error::ManifestNotFound) if the archive doesn't contain a manifest.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use thunderstore::models::PackageManifest;
+    ///
+    /// let data = std::fs::read("BepInEx-BepInExPack-5.4.2100.zip")?;
+    /// let manifest = PackageManifest::from_zip_bytes(&data)?;
+    /// println!("{} v{}", manifest.name, manifest.version_number);
+    /// # Ok::<(), thunderstore::Error>(())
+    /// ```
+    #[cfg(feature = "zip")]
+    pub fn from_zip_bytes(data: &[u8]) -> crate::Result<Self> {
+        use crate::Error;
+        use std::io::{Cursor, Read};
+        use zip::{result::ZipError, ZipArchive};
+
+        let mut archive = ZipArchive::new(Cursor::new(data))?;
+
+        let mut file = match archive.by_name("manifest.json") {
+            Ok(file) => file,
+            Err(ZipError::FileNotFound) => return Err(Error::ManifestNotFound),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+
+        // manifests are often saved with a byte order mark, which serde_json rejects
+        let contents = contents.trim_start_matches('\u{feff}');
+        Ok(serde_json::from_str(contents)?)
+    }
+
+    /// Parses [`PackageManifest::dependencies`] into [`DependencyReq`]s.
+    ///
+    /// Returns [`Error::InvalidPackageId`](crate::Error::InvalidPackageId) if a dependency
+    /// string is neither `namespace-name-version` nor `namespace-name`.
+    pub fn dependency_reqs(&self) -> crate::Result<Vec<DependencyReq>> {
+        self.dependencies.iter().map(|dep| dep.parse()).collect()
+    }
+}
+
+/// A dependency of a package, as written in a manifest.
+///
+/// Dependencies are usually pinned to an exact version, but a manifest may also
+/// refer to a package without a version, leaving the choice to the resolver.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DependencyReq {
+    /// A specific version, written as `namespace-name-version`.
+    Exact(crate::VersionId),
+    /// Any version of a package, written as `namespace-name`.
+    Any(crate::PackageId),
+}
+
+impl DependencyReq {
+    /// Returns the package this dependency refers to.
+    pub fn package_id(&self) -> crate::PackageId {
+        match self {
+            DependencyReq::Exact(version) => version.into(),
+            DependencyReq::Any(package) => package.clone(),
+        }
+    }
+
+    /// Returns the pinned version, or `None` if any version is accepted.
+    pub fn into_version_id(self) -> Option<crate::VersionId> {
+        match self {
+            DependencyReq::Exact(version) => Some(version),
+            DependencyReq::Any(_) => None,
+        }
+    }
+}
+
+impl std::str::FromStr for DependencyReq {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.matches('-').count() {
+            1 => Ok(DependencyReq::Any(s.parse()?)),
+            2 => Ok(DependencyReq::Exact(s.parse()?)),
+            _ => Err(crate::Error::InvalidPackageId),
+        }
+    }
+}
+
+/// Reads the `README.md` file from the root of a package's archive.
+///
+/// Returns `None` if the archive can't be read or doesn't contain a readme.
+/// Use [`Client::get_readme`](crate::Client::get_readme) to fetch the readme without downloading the package.
+#[cfg(feature = "zip")]
+pub fn readme_from_zip_bytes(data: &[u8]) -> Option<String> {
+    use std::io::{Cursor, Read};
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).ok()?;
+    let mut file = archive.by_name("README.md").ok()?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).ok()?;
+
+    Some(contents.trim_start_matches('\u{feff}').to_owned())
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PackageInstaller {
     pub identifier: String,
@@ -114,13 +286,13 @@ pub struct UserMediaInitiateUploadParams {
     pub file_size_bytes: u64,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserMediaInitiateUploadResponse {
     pub user_media: UserMedia,
     pub upload_urls: Vec<UploadPartUrl>,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserMedia {
     pub uuid: Option<Uuid>,
     pub filename: String,
@@ -130,7 +302,7 @@ pub struct UserMedia {
     pub status: UserMediaStatus,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum UserMediaStatus {
     Initial,
@@ -141,7 +313,7 @@ pub enum UserMediaStatus {
     UploadAborted,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UploadPartUrl {
     pub part_number: u32,
     pub url: String,
@@ -154,7 +326,7 @@ pub struct UserMediaFinishUploadParams {
     pub parts: Vec<CompletedPart>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CompletedPart {
     #[serde(rename = "ETag")]
     pub tag: String,
@@ -168,6 +340,21 @@ pub struct PackageSubmissionResult {
     pub available_communities: Vec<AvailableCommunity>,
 }
 
+/// The user who owns the client's API token, see [`Client::get_current_user`](crate::Client::get_current_user).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    pub username: String,
+    /// What the user is allowed to do, like `package.rate`.
+    #[serde(default)]
+    pub capabilities: HashSet<String>,
+    /// The full names of the packages the user has rated.
+    #[serde(default, rename = "ratedPackages")]
+    pub rated_packages: Vec<String>,
+    /// The names of the teams the user is a member of, which are also the namespaces they can publish under.
+    #[serde(default)]
+    pub teams: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AvailableCommunity {
     pub community: Community,
@@ -190,6 +377,12 @@ pub struct PackageCategory {
     pub slug: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommunityCategory {
+    pub name: String,
+    pub slug: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PackageVersionMetrics {
     pub downloads: u64,
@@ -198,6 +391,19 @@ pub struct PackageVersionMetrics {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PackageMetrics {
     pub downloads: u64,
+    pub rating_score: u32,
+    pub latest_version: semver::Version,
+}
+
+/// Whether an installed package has a newer version, see [`Client::check_updates`](crate::Client::check_updates).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateInfo {
+    /// The installed version.
+    pub current: crate::VersionId,
+    /// The latest version available in the community.
+    pub latest: crate::VersionId,
+    /// Whether `latest` is newer than `current`.
+    pub update_available: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -210,11 +416,121 @@ pub struct RenderMarkdownResponse {
     pub html: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RatingState {
+    Rated,
+    Unrated,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RatePackageParams {
+    pub target_state: RatingState,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RatePackageResponse {
+    pub state: RatingState,
+    pub score: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MarkdownResponse {
     pub markdown: String,
 }
 
+/// A section of a changelog, see [`Client::get_changelog_sections`](crate::Client::get_changelog_sections).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogEntry {
+    /// The version from the section's heading, or `None` for content before the first version heading.
+    pub version: Option<semver::Version>,
+    /// The markdown of the section, without the heading.
+    pub body: String,
+}
+
+impl ChangelogEntry {
+    /// Splits a markdown changelog into sections by its version headings.
+    ///
+    /// Any heading which contains a version counts, so `## 1.2.3`, `# v1.2.3`
+    /// and `## [1.2] - 2024-01-01` are all recognized. Versions with only a major and minor
+    /// component get a patch of zero. Content before the first version heading, like a
+    /// title, is put in a leading entry without a version.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use thunderstore::models::ChangelogEntry;
+    ///
+    /// let entries = ChangelogEntry::parse("# Changelog\n\n## 1.1.0\n- Fixed\n\n## v1.0.0\n- Released");
+    ///
+    /// assert_eq!(entries.len(), 3);
+    /// assert_eq!(entries[0].version, None);
+    /// assert_eq!(entries[1].version, Some(semver::Version::new(1, 1, 0)));
+    /// assert_eq!(entries[2].body, "- Released");
+    /// ```
+    pub fn parse(markdown: &str) -> Vec<Self> {
+        let mut entries = Vec::new();
+        let mut version = None;
+        let mut body = String::new();
+        let mut in_code_block = false;
+
+        for line in markdown.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+
+            let heading_version = (!in_code_block).then(|| heading_version(line)).flatten();
+
+            match heading_version {
+                Some(next) => {
+                    Self::push(&mut entries, version.take(), &body);
+                    version = Some(next);
+                    body.clear();
+                }
+                None => {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+            }
+        }
+
+        Self::push(&mut entries, version, &body);
+        entries
+    }
+
+    fn push(entries: &mut Vec<Self>, version: Option<semver::Version>, body: &str) {
+        let body = body.trim();
+
+        // skip the leading entry if there's nothing before the first heading
+        if version.is_some() || !body.is_empty() {
+            entries.push(Self {
+                version,
+                body: body.to_owned(),
+            });
+        }
+    }
+}
+
+/// Returns the first version in a markdown heading, if `line` is one.
+fn heading_version(line: &str) -> Option<semver::Version> {
+    let text = line.trim_start().strip_prefix('#')?.trim_start_matches('#');
+
+    text.split(|c: char| c.is_whitespace() || "[](),:".contains(c))
+        .find_map(|word| {
+            let word = word.strip_prefix(['v', 'V']).unwrap_or(word);
+
+            semver::Version::parse(word).ok().or_else(|| {
+                // be lenient about versions like 1.2
+                let (major, minor) = word.split_once('.')?;
+                Some(semver::Version::new(
+                    major.parse().ok()?,
+                    minor.parse().ok()?,
+                    0,
+                ))
+            })
+        })
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PackageIndexEntry {
     pub namespace: String,
@@ -270,6 +586,19 @@ pub struct Package {
     pub community_listings: Vec<PackageListingExperimental>,
 }
 
+impl Package {
+    /// Returns the package's listing in the given community, if it's listed there.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    pub fn listing_in(&self, community: impl AsRef<str>) -> Option<&PackageListingExperimental> {
+        let community = community.as_ref();
+
+        self.community_listings
+            .iter()
+            .find(|listing| listing.community == community)
+    }
+}
+
 impl PartialEq for Package {
     fn eq(&self, other: &Self) -> bool {
         self.full_name == other.full_name
@@ -282,6 +611,84 @@ impl Hash for Package {
     }
 }
 
+/// The fields shared by [`Package`] and [`PackageV1`], for code which consumes both APIs.
+///
+/// Fields which only exist on one of the models are left out, such as the versions
+/// and categories of a [`PackageV1`] and the community listings of a [`Package`].
+///
+/// ## Example
+///
+/// ```no_run
+/// use thunderstore::models::PackageSummary;
+///
+/// # async fn run() -> thunderstore::Result<()> {
+/// let client = thunderstore::Client::new();
+///
+/// let a = PackageSummary::from(&client.get_package("Kesomannen-GaleModManager").await?);
+/// let b: Vec<PackageSummary> = client
+///     .list_packages_v1("lethal-company")
+///     .await?
+///     .iter()
+///     .map(PackageSummary::from)
+///     .collect();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackageSummary {
+    pub namespace: String,
+    pub name: String,
+    pub full_name: String,
+    pub package_url: Url,
+    pub date_created: DateTime<Utc>,
+    pub date_updated: DateTime<Utc>,
+    pub rating_score: i64,
+    pub is_pinned: bool,
+    pub is_deprecated: bool,
+    /// The downloads of all versions combined.
+    pub total_downloads: u64,
+}
+
+impl From<&Package> for PackageSummary {
+    fn from(package: &Package) -> Self {
+        Self {
+            namespace: package.namespace.clone(),
+            name: package.name.clone(),
+            full_name: package.full_name.clone(),
+            package_url: package.package_url.clone(),
+            date_created: package.date_created,
+            date_updated: package.date_updated,
+            rating_score: package.rating_score.into(),
+            is_pinned: package.is_pinned,
+            is_deprecated: package.is_deprecated,
+            total_downloads: package.total_downloads.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&PackageV1> for PackageSummary {
+    /// The namespace is taken from [`PackageV1::owner`], and the total downloads
+    /// are summed from [`PackageV1::versions`].
+    fn from(package: &PackageV1) -> Self {
+        Self {
+            namespace: package.owner.clone(),
+            name: package.name.clone(),
+            full_name: package.full_name.clone(),
+            package_url: package.package_url.clone(),
+            date_created: package.date_created,
+            date_updated: package.date_updated,
+            rating_score: package.rating_score.into(),
+            is_pinned: package.is_pinned,
+            is_deprecated: package.is_deprecated,
+            total_downloads: package
+                .versions
+                .iter()
+                .map(|v| u64::from(v.downloads))
+                .sum(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PackageListingExperimental {
     pub has_nsfw_content: bool,
@@ -297,3 +704,576 @@ pub enum ReviewStatus {
     Approved,
     Rejected,
 }
+
+/// A page of results from a cursor-paginated endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaginatedResponse<T> {
+    pub pagination: Pagination,
+    pub results: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    pub next_link: Option<Url>,
+    pub previous_link: Option<Url>,
+}
+
+/// The cursors pointing to the pages around a paginated response.
+///
+/// Pass [`CursorState::next`] or [`CursorState::prev`] back into the method
+/// that returned the [`Page`] to fetch the neighbouring pages.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CursorState {
+    pub next: Option<String>,
+    pub prev: Option<String>,
+}
+
+impl From<&Pagination> for CursorState {
+    fn from(pagination: &Pagination) -> Self {
+        fn cursor(link: &Option<Url>) -> Option<String> {
+            link.as_ref()?
+                .query_pairs()
+                .find(|(key, _)| key == "cursor")
+                .map(|(_, value)| value.into_owned())
+        }
+
+        Self {
+            next: cursor(&pagination.next_link),
+            prev: cursor(&pagination.previous_link),
+        }
+    }
+}
+
+/// A single page of a paginated response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub cursor: CursorState,
+}
+
+impl<T> Page<T> {
+    /// Whether there is a page after this one.
+    pub fn has_next(&self) -> bool {
+        self.cursor.next.is_some()
+    }
+
+    /// Whether there is a page before this one.
+    pub fn has_prev(&self) -> bool {
+        self.cursor.prev.is_some()
+    }
+}
+
+impl<T> From<PaginatedResponse<T>> for Page<T> {
+    fn from(response: PaginatedResponse<T>) -> Self {
+        Self {
+            cursor: (&response.pagination).into(),
+            items: response.results,
+        }
+    }
+}
+
+/// Criteria for [`Client::stream_packages_v1_filtered`](crate::Client::stream_packages_v1_filtered).
+///
+/// The default filter matches every package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageFilter {
+    skip_deprecated: bool,
+    skip_nsfw: bool,
+    categories: Vec<String>,
+}
+
+impl PackageFilter {
+    /// Creates a filter which matches every package.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips deprecated packages.
+    pub fn skip_deprecated(mut self) -> Self {
+        self.skip_deprecated = true;
+        self
+    }
+
+    /// Skips packages with NSFW content.
+    pub fn skip_nsfw(mut self) -> Self {
+        self.skip_nsfw = true;
+        self
+    }
+
+    /// Only matches packages in the given category, such as `Modpacks`.
+    ///
+    /// If called multiple times, packages must be in all of the categories.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    /// Checks a package's JSON without deserializing all of it.
+    pub(crate) fn matches_json(&self, json: &[u8]) -> serde_json::Result<bool> {
+        #[derive(Deserialize)]
+        struct Fields {
+            categories: HashSet<String>,
+            is_deprecated: bool,
+            has_nsfw_content: bool,
+        }
+
+        if *self == Self::default() {
+            return Ok(true);
+        }
+
+        let fields: Fields = serde_json::from_slice(json)?;
+
+        if (self.skip_deprecated && fields.is_deprecated)
+            || (self.skip_nsfw && fields.has_nsfw_content)
+        {
+            return Ok(false);
+        }
+
+        Ok(self
+            .categories
+            .iter()
+            .all(|category| fields.categories.contains(category)))
+    }
+}
+
+/// A property to sort [`PackageV1`]s by, see [`SortKey::compare`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortKey {
+    /// The total downloads of all versions.
+    Downloads,
+    /// The rating score.
+    Rating,
+    /// The date the package was last updated.
+    DateUpdated,
+    /// The full name of the package, compared case-insensitively.
+    Name,
+}
+
+impl SortKey {
+    /// Compares two packages by this key, in ascending order.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use thunderstore::models::SortKey;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let mut packages = client.list_packages_v1("lethal-company").await?;
+    /// packages.sort_by(|a, b| SortKey::Rating.compare(b, a)); // highest rated first
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compare(&self, a: &PackageV1, b: &PackageV1) -> cmp::Ordering {
+        match self {
+            SortKey::Downloads => a.total_downloads().cmp(&b.total_downloads()),
+            SortKey::Rating => a.rating_score.cmp(&b.rating_score),
+            SortKey::DateUpdated => a.date_updated.cmp(&b.date_updated),
+            SortKey::Name => a.full_name.to_lowercase().cmp(&b.full_name.to_lowercase()),
+        }
+    }
+}
+
+/// The direction to sort in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    Ascending,
+    #[default]
+    Descending,
+}
+
+/// The order in which to return search results.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum PackageOrdering {
+    MostDownloaded,
+    Newest,
+    #[default]
+    LastUpdated,
+}
+
+impl PackageOrdering {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PackageOrdering::MostDownloaded => "most-downloaded",
+            PackageOrdering::Newest => "newest",
+            PackageOrdering::LastUpdated => "last-updated",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IconValidatorParams {
+    pub namespace: String,
+    pub icon_data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ManifestV1ValidatorParams {
+    pub namespace: String,
+    pub manifest_data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReadmeValidatorParams {
+    pub readme_data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidatorResponse {
+    pub success: bool,
+}
+
+/// The results of validating every part of a package, see
+/// [`Client::validate_package`](crate::Client::validate_package).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageValidation {
+    pub manifest: ValidationOutcome,
+    pub readme: ValidationOutcome,
+    pub icon: ValidationOutcome,
+}
+
+impl PackageValidation {
+    /// Returns `true` if every part of the package is valid.
+    pub fn is_valid(&self) -> bool {
+        self.manifest.is_valid() && self.readme.is_valid() && self.icon.is_valid()
+    }
+
+    /// Returns an iterator over all error messages, regardless of part or field.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.manifest
+            .messages()
+            .chain(self.readme.messages())
+            .chain(self.icon.messages())
+    }
+}
+
+/// The result of validating part of a package before submission.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationOutcome {
+    /// The reasons the validation failed, keyed by the name of the offending field.
+    /// General errors are keyed by `non_field_errors`.
+    ///
+    /// This is empty if the validation succeeded, or if it failed without a reason.
+    pub errors: HashMap<String, Vec<String>>,
+    /// Whether the server reported a failure without giving any reasons, in which case
+    /// `errors` is empty.
+    #[serde(default)]
+    pub failed_without_reason: bool,
+}
+
+impl ValidationOutcome {
+    /// Returns `true` if the validation succeeded.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty() && !self.failed_without_reason
+    }
+
+    /// Returns an iterator over all error messages, regardless of field.
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.errors.values().flatten().map(String::as_str)
+    }
+
+    pub(crate) fn from_error_body(body: &str) -> Self {
+        Self {
+            errors: field_errors_from_body(body),
+            failed_without_reason: false,
+        }
+    }
+}
+
+/// The reasons a package submission was rejected, returned in [`Error::Submission`](crate::Error::Submission).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmissionError {
+    /// The error messages, keyed by the name of the offending field, such as `communities`.
+    /// General errors are keyed by `non_field_errors`.
+    pub field_errors: HashMap<String, Vec<String>>,
+}
+
+impl SubmissionError {
+    pub(crate) fn from_error_body(body: &str) -> Self {
+        Self {
+            field_errors: field_errors_from_body(body),
+        }
+    }
+}
+
+impl std::fmt::Display for SubmissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut fields: Vec<_> = self.field_errors.iter().collect();
+        fields.sort();
+
+        for (i, (field, messages)) in fields.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}: {}", field, messages.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the body of a rejected request, which is usually a map of
+/// field names to lists of messages, but can also be a single message.
+fn field_errors_from_body(body: &str) -> HashMap<String, Vec<String>> {
+    match serde_json::from_str::<HashMap<String, serde_json::Value>>(body) {
+        Ok(fields) => fields
+            .into_iter()
+            .map(|(field, value)| {
+                let messages = match value {
+                    serde_json::Value::String(message) => vec![message],
+                    serde_json::Value::Array(values) => values
+                        .into_iter()
+                        .map(|value| match value {
+                            serde_json::Value::String(message) => message,
+                            other => other.to_string(),
+                        })
+                        .collect(),
+                    other => vec![other.to_string()],
+                };
+
+                (field, messages)
+            })
+            .collect(),
+        Err(_) => HashMap::from([("non_field_errors".to_owned(), vec![body.to_owned()])]),
+    }
+}
+
+/// The wiki of a package.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Wiki {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub datetime_created: DateTime<Utc>,
+    pub datetime_updated: DateTime<Utc>,
+    pub pages: Vec<WikiPageIndex>,
+}
+
+/// A page of a [`Wiki`], without its content.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WikiPageIndex {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub datetime_created: DateTime<Utc>,
+    pub datetime_updated: DateTime<Utc>,
+}
+
+/// A page of a [`Wiki`], including its markdown content.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WikiPage {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub datetime_created: DateTime<Utc>,
+    pub datetime_updated: DateTime<Utc>,
+    #[serde(rename = "markdown_content")]
+    pub content: String,
+}
+
+/// A wiki together with the package it belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ListedWiki {
+    pub namespace: String,
+    pub name: String,
+    pub wiki: Wiki,
+}
+
+/// A page of wikis, as returned by [`Client::get_wikis`](crate::Client::get_wikis).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WikisResponse {
+    pub results: Vec<ListedWiki>,
+    /// Pass this to [`Client::get_wikis`](crate::Client::get_wikis) to fetch the next page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    pub has_more: bool,
+}
+
+/// The mod list of an r2modman profile, stored as `export.r2x` in the profile's archive.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileManifest {
+    pub profile_name: String,
+    pub mods: Vec<ProfileMod>,
+}
+
+impl ProfileManifest {
+    /// Reads the manifest from the `export.r2x` file of a profile archive,
+    /// as returned by [`Client::get_profile`](crate::Client::get_profile).
+    ///
+    /// Returns [`Error::ProfileManifestNotFound`](crate::Error::ProfileManifestNotFound)
+    /// if the archive doesn't contain a manifest.
+    #[cfg(feature = "zip")]
+    pub fn from_zip_bytes(data: &[u8]) -> crate::Result<Self> {
+        use crate::Error;
+        use std::io::Cursor;
+        use zip::{result::ZipError, ZipArchive};
+
+        let mut archive = ZipArchive::new(Cursor::new(data))?;
+
+        let file = match archive.by_name("export.r2x") {
+            Ok(file) => file,
+            Err(ZipError::FileNotFound) => return Err(Error::ProfileManifestNotFound),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(serde_yaml::from_reader(file)?)
+    }
+}
+
+/// A builder for creating r2modman profiles, which can be shared with
+/// `Client::create_profile_from_builder`. Building a profile requires the `zip` feature.
+///
+/// ## Example
+///
+/// ```no_run
+/// use thunderstore::{models::ProfileBuilder, VersionId};
+///
+/// # #[cfg(feature = "zip")]
+/// # async fn run() -> thunderstore::Result<()> {
+/// let client = thunderstore::Client::new();
+///
+/// let profile = ProfileBuilder::new("Modded")
+///     .add_mod(VersionId::new("BepInEx", "BepInExPack", "5.4.2100"), true)
+///     .add_mod(VersionId::new("Kesomannen", "CoolMod", "1.0.0"), false);
+///
+/// let key = client.create_profile_from_builder(profile).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProfileBuilder {
+    name: String,
+    mods: Vec<(crate::VersionId, bool)>,
+}
+
+impl ProfileBuilder {
+    /// Creates a new profile with the given name and no mods.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            mods: Vec::new(),
+        }
+    }
+
+    /// Adds a mod to the profile.
+    pub fn add_mod(mut self, id: crate::VersionId, enabled: bool) -> Self {
+        self.mods.push((id, enabled));
+        self
+    }
+
+    /// Creates the [`ProfileManifest`] of the profile.
+    ///
+    /// Returns [`Error::InvalidSemver`](crate::Error::InvalidSemver) if a version isn't valid semver.
+    pub fn manifest(&self) -> crate::Result<ProfileManifest> {
+        let mods = self
+            .mods
+            .iter()
+            .map(|(id, enabled)| {
+                Ok(ProfileMod {
+                    name: format!("{}-{}", id.namespace(), id.name()),
+                    version: id.parsed_version()?,
+                    enabled: *enabled,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+
+        Ok(ProfileManifest {
+            profile_name: self.name.clone(),
+            mods,
+        })
+    }
+
+    /// Creates the profile's archive, containing only an `export.r2x` file.
+    #[cfg(feature = "zip")]
+    pub fn build(&self) -> crate::Result<Vec<u8>> {
+        use std::io::{Cursor, Write};
+        use zip::{write::SimpleFileOptions, ZipWriter};
+
+        let manifest = serde_yaml::to_string(&self.manifest()?)?;
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("export.r2x", SimpleFileOptions::default())?;
+        writer.write_all(manifest.as_bytes())?;
+
+        Ok(writer.finish()?.into_inner())
+    }
+}
+
+/// A mod in a [`ProfileManifest`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProfileMod {
+    /// The full name of the package, in the format `namespace-name`.
+    pub name: String,
+    #[serde(with = "profile_version")]
+    pub version: semver::Version,
+    pub enabled: bool,
+}
+
+impl ProfileMod {
+    /// Returns the id of the installed version of this mod.
+    pub fn version_id(&self) -> crate::Result<crate::VersionId> {
+        format!("{}-{}", self.name, self.version).parse()
+    }
+}
+
+/// r2modman stores versions as a `{ major, minor, patch }` map.
+mod profile_version {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct ProfileVersion {
+        major: u64,
+        minor: u64,
+        patch: u64,
+    }
+
+    pub fn serialize<S: Serializer>(
+        version: &semver::Version,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ProfileVersion {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<semver::Version, D::Error> {
+        let version = ProfileVersion::deserialize(deserializer)?;
+        Ok(semver::Version::new(
+            version.major,
+            version.minor,
+            version.patch,
+        ))
+    }
+}
+
+/// A team, which packages are published under.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Team {
+    pub identifier: u64,
+    pub name: String,
+    pub donation_link: Option<Url>,
+}
+
+/// A member of a [`Team`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TeamMember {
+    pub identifier: u64,
+    pub username: String,
+    pub avatar: Option<Url>,
+    pub role: TeamMemberRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TeamMemberRole {
+    Owner,
+    Member,
+}
@@ -1,5 +1,134 @@
 use super::*;
 use futures_util::{pin_mut, TryStreamExt};
+use std::{collections::HashMap, fs};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use transport::MockTransport;
+
+/// Creates a client which responds with canned JSON bodies through a [`MockTransport`],
+/// keyed by request path and query. Unknown paths get a 404.
+///
+/// Occurrences of [`MOCK_BASE_URL`] in the bodies are replaced with the client's base URL.
+async fn mock_client(routes: &[(&str, &str)]) -> Client {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, body)| (*path, 200, *body))
+        .collect();
+    mock_client_with_status(&routes).await
+}
+
+/// Like [`mock_client`], but with a custom status code for each route.
+async fn mock_client_with_status(routes: &[(&str, u16, &str)]) -> Client {
+    let transport = routes
+        .iter()
+        .fold(MockTransport::new(), |transport, (path, status, body)| {
+            transport.with_status(
+                *path,
+                *status,
+                body.replace(MOCK_BASE_URL, DEFAULT_BASE_URL),
+            )
+        });
+
+    Client::builder().with_transport(transport).build().unwrap()
+}
+
+/// Like [`mock_client`], but starts a local HTTP server and points the client at it.
+///
+/// Only use this for tests which need a real connection, like timeouts, proxies, truncated or
+/// streamed bodies, and headers the transport doesn't set (`Content-Length` and `ETag: "mock"`).
+/// Everything else should use [`mock_client`].
+async fn mock_server(routes: &[(&str, &str)]) -> Client {
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, body)| (*path, 200, *body))
+        .collect();
+    mock_server_with_status(&routes).await
+}
+
+/// Like [`mock_server`], but with a custom status code for each route.
+async fn mock_server_with_status(routes: &[(&str, u16, &str)]) -> Client {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let routes: HashMap<String, (u16, String)> = routes
+        .iter()
+        .map(|(path, status, body)| {
+            let body = body.replace(MOCK_BASE_URL, &base_url);
+            (path.to_string(), (*status, body))
+        })
+        .collect();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let routes = routes.clone();
+
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                let target = request.split_whitespace().nth(1).unwrap_or_default();
+
+                let (status, body) = match routes.get(target) {
+                    Some((status, body)) => (*status, body.as_str()),
+                    None => (404, "{}"),
+                };
+
+                let response = format!(
+                    "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nETag: \"mock\"\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    StatusCode::from_u16(status).unwrap().canonical_reason().unwrap_or_default(),
+                    body.len(),
+                    body
+                );
+
+                socket.write_all(response.as_bytes()).await.ok();
+            });
+        }
+    });
+
+    Client::builder().with_base_url(base_url).build().unwrap()
+}
+
+/// Placeholder for the base URL of the mock client, see [`mock_client`].
+const MOCK_BASE_URL: &str = "$MOCK_BASE_URL";
+
+/// Like [`page_json`], but with a `next_link` to `next_path` on the mock server.
+fn linked_page_json(results: &[String], next_path: Option<&str>) -> String {
+    let next_link = match next_path {
+        Some(path) => format!("\"{}{}\"", MOCK_BASE_URL, path),
+        None => "null".to_owned(),
+    };
+
+    format!(
+        r#"{{"pagination":{{"next_link":{},"previous_link":null}},"results":[{}]}}"#,
+        next_link,
+        results.join(",")
+    )
+}
+
+/// Creates a JSON page of a cursor-paginated response.
+fn page_json(results: &[String], next_cursor: Option<&str>) -> String {
+    let next_link = match next_cursor {
+        Some(cursor) => format!("\"https://thunderstore.io/?cursor={}\"", cursor),
+        None => "null".to_owned(),
+    };
+
+    format!(
+        r#"{{"pagination":{{"next_link":{},"previous_link":null}},"results":[{}]}}"#,
+        next_link,
+        results.join(",")
+    )
+}
 
 #[test]
 fn version_id_new_works() {
@@ -23,6 +152,150 @@ fn parse_version_id_works() {
     assert_eq!(id.version(), "0.16.0");
 }
 
+#[test]
+fn parsed_version_fails_on_invalid_semver() {
+    let id: VersionId = "Author-Mod-not.a.version".parse().unwrap();
+    assert!(matches!(id.parsed_version(), Err(Error::InvalidSemver(_))));
+
+    let id: VersionId = "Author-Mod-1.0.0".parse().unwrap();
+    assert_eq!(id.parsed_version().unwrap(), semver::Version::new(1, 0, 0));
+}
+
+#[test]
+fn parse_validated_rejects_invalid_semver() {
+    assert!(matches!(
+        VersionId::parse_validated("Author-Mod-not.a.version"),
+        Err(Error::InvalidSemver(_))
+    ));
+    assert!(matches!(
+        VersionId::parse_validated("Author-Mod"),
+        Err(Error::InvalidPackageId)
+    ));
+    assert!(VersionId::parse_validated("Author-Mod-1.0.0").is_ok());
+}
+
+#[test]
+fn eq_ignore_case_works() {
+    let a = PackageId::new("BepInEx", "BepInExPack");
+    let b = PackageId::new("bepinex", "bepinexpack");
+    assert!(a.eq_ignore_case(&b));
+    assert_ne!(a, b);
+
+    let a = VersionId::new("BepInEx", "BepInExPack", "5.4.2100");
+    let b = VersionId::new("bepinex", "BEPINEXPACK", "5.4.2100");
+    let c = VersionId::new("bepinex", "bepinexpack", "5.4.2101");
+    assert!(a.eq_ignore_case(&b));
+    assert!(!a.eq_ignore_case(&c));
+}
+
+#[test]
+fn sort_versions_uses_semver() {
+    let mut ids: Vec<VersionId> = [
+        "B-Mod-1.0.0",
+        "A-Mod-1.10.0",
+        "A-Mod-latest",
+        "A-Mod-1.2.0",
+        "A-Mod-1.2.0-beta",
+    ]
+    .iter()
+    .map(|id| id.parse().unwrap())
+    .collect();
+
+    sort_versions(&mut ids);
+
+    let ids: Vec<_> = ids.iter().map(VersionId::as_str).collect();
+    assert_eq!(
+        ids,
+        [
+            "A-Mod-1.2.0-beta",
+            "A-Mod-1.2.0",
+            "A-Mod-1.10.0",
+            "A-Mod-latest",
+            "B-Mod-1.0.0"
+        ]
+    );
+}
+
+#[test]
+fn community_slug_validates() {
+    for slug in ["lethal-company", "riskofrain2", "h3vr"] {
+        assert_eq!(slug.parse::<CommunitySlug>().unwrap().as_str(), slug);
+    }
+
+    for slug in [
+        "",
+        "Lethal-Company",
+        "lethal company",
+        "lethal--company",
+        "-valheim",
+    ] {
+        assert!(matches!(
+            slug.parse::<CommunitySlug>(),
+            Err(Error::InvalidCommunitySlug(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn community_slug_is_accepted_by_methods() -> Result<()> {
+    let client = mock_client(&[("/c/valheim/api/v1/package/", "[]")]).await;
+    let slug: CommunitySlug = "valheim".parse()?;
+
+    assert!(client.list_packages_v1(&slug).await?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn package_id_from_version_id_works() {
+    let version = VersionId::new("BepInEx", "BepInExPack", "5.4.2100");
+    let package = PackageId::from(&version);
+    assert_eq!(package, PackageId::new("BepInEx", "BepInExPack"));
+    assert_eq!(package.name(), "BepInExPack");
+}
+
+#[test]
+fn package_id_with_version_works() {
+    let package = PackageId::new("BepInEx", "BepInExPack");
+    let version = semver::Version::new(5, 4, 2100);
+    let expected = VersionId::new("BepInEx", "BepInExPack", "5.4.2100");
+
+    assert_eq!(package.with_version(&version), expected);
+    assert_eq!(package.with_version("5.4.2100"), expected);
+    assert_eq!(VersionId::from((&package, &version)), expected);
+}
+
+#[test]
+fn dependency_reqs_distinguish_exact_versions() -> Result<()> {
+    use models::DependencyReq;
+
+    let mut manifest: models::PackageManifest = serde_json::from_str(
+        r#"{
+            "name": "CoolMod",
+            "version_number": "1.0.0",
+            "website_url": "",
+            "description": "",
+            "dependencies": ["BepInEx-BepInExPack-5.4.2100", "Kesomannen-Lib"]
+        }"#,
+    )?;
+
+    let reqs = manifest.dependency_reqs()?;
+    assert_eq!(
+        reqs,
+        [
+            DependencyReq::Exact(VersionId::new("BepInEx", "BepInExPack", "5.4.2100")),
+            DependencyReq::Any(PackageId::new("Kesomannen", "Lib")),
+        ]
+    );
+    assert_eq!(reqs[1].package_id(), PackageId::new("Kesomannen", "Lib"));
+    assert!(reqs[1].clone().into_version_id().is_none());
+
+    manifest.dependencies.push("Invalid".to_owned());
+    assert!(manifest.dependency_reqs().is_err());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn get_package_index_works() -> Result<()> {
     Client::new().get_package_index().await?;
@@ -37,7 +310,7 @@ async fn stream_packages_v1_works() -> Result<()> {
     pin_mut!(stream);
 
     let mut count = 0;
-    while let Some(_) = stream.try_next().await? {
+    while stream.try_next().await?.is_some() {
         count += 1;
     }
 
@@ -61,13 +334,29 @@ async fn get_package_fails_when_not_found() -> Result<()> {
     let client = Client::new();
 
     match client.get_package(("Kesomannen", "GaleModManager2")).await {
-        Err(Error::NotFound) => (),
+        Err(Error::NotFound { .. }) => (),
         other => panic!("expected NotFound error, got {:?}", other),
     }
 
     Ok(())
 }
 
+#[tokio::test]
+async fn get_downloads_is_per_version() -> Result<()> {
+    let client = Client::new();
+
+    let package = client
+        .get_metrics("lethal-company", ("Kesomannen", "GaleModManager"))
+        .await?;
+    let version = client
+        .get_downloads("lethal-company", ("Kesomannen", "GaleModManager", "0.6.0"))
+        .await?;
+
+    assert!(version < package.downloads);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn get_version_works() -> Result<()> {
     let client = Client::new();
@@ -104,3 +393,3249 @@ async fn get_readme_works() -> Result<()> {
         .await?;
     Ok(())
 }
+
+fn upload_part(part_number: u32) -> models::UploadPartUrl {
+    models::UploadPartUrl {
+        part_number,
+        url: format!("https://example.com/{}", part_number),
+        offset: (part_number as u64 - 1) * 10,
+        length: 10,
+    }
+}
+
+fn completed_part(part_number: u32) -> models::CompletedPart {
+    models::CompletedPart {
+        tag: format!("etag-{}", part_number),
+        part_number,
+    }
+}
+
+#[test]
+fn remaining_parts_skips_completed() -> Result<()> {
+    let urls = (1..=4).map(upload_part).collect();
+    let completed = [completed_part(1), completed_part(3)];
+
+    let remaining = usermedia::remaining_parts(urls, &completed)?;
+    let numbers: Vec<_> = remaining.iter().map(|part| part.part_number).collect();
+    assert_eq!(numbers, [2, 4]);
+
+    Ok(())
+}
+
+#[test]
+fn remaining_parts_fails_on_unknown_part() {
+    let urls = (1..=2).map(upload_part).collect();
+    let completed = [completed_part(3)];
+
+    assert!(matches!(
+        usermedia::remaining_parts(urls, &completed),
+        Err(Error::InvalidUploadParts)
+    ));
+}
+
+#[test]
+fn remaining_parts_fails_on_duplicate_part() {
+    let urls = (1..=2).map(upload_part).collect();
+    let completed = [completed_part(1), completed_part(1)];
+
+    assert!(matches!(
+        usermedia::remaining_parts(urls, &completed),
+        Err(Error::InvalidUploadParts)
+    ));
+}
+
+#[test]
+fn part_slice_checks_bounds() -> Result<()> {
+    let data = Bytes::from(vec![0; 25]);
+
+    assert_eq!(usermedia::part_slice(&data, &upload_part(2))?.len(), 10);
+
+    assert!(matches!(
+        usermedia::part_slice(&data, &upload_part(3)),
+        Err(Error::InvalidUploadPart { part_number: 3 })
+    ));
+
+    let mut overflowing = upload_part(1);
+    overflowing.offset = u64::MAX;
+    assert!(matches!(
+        usermedia::part_slice(&data, &overflowing),
+        Err(Error::InvalidUploadPart { part_number: 1 })
+    ));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "blocking", feature = "test-util"))]
+#[test]
+fn blocking_get_package_works() -> Result<()> {
+    use transport::{fixtures, MockTransport};
+
+    let transport = MockTransport::new()
+        .with_response(
+            "/api/experimental/package/Kesomannen/GaleModManager/",
+            fixtures::PACKAGE,
+        )
+        .with_response(
+            "/api/experimental/package/Kesomannen/GaleModManager/0.6.0/",
+            fixtures::VERSION,
+        );
+    let client = blocking::Client::builder()
+        .with_transport(transport)
+        .build_blocking()?;
+
+    let package = client.get_package(("Kesomannen", "GaleModManager"))?;
+    assert_eq!(package.full_name, "Kesomannen-GaleModManager");
+
+    let version = client.get_version("Kesomannen-GaleModManager-0.6.0")?;
+    assert_eq!(version.version_number, semver::Version::new(0, 6, 0));
+
+    Ok(())
+}
+
+#[test]
+fn cursor_state_from_pagination_works() {
+    let pagination = models::Pagination {
+        next_link: Some(
+            "https://thunderstore.io/api/experimental/community/?cursor=cD0yMDIz"
+                .parse()
+                .unwrap(),
+        ),
+        previous_link: None,
+    };
+
+    let state = models::CursorState::from(&pagination);
+    assert_eq!(state.next.as_deref(), Some("cD0yMDIz"));
+    assert_eq!(state.prev, None);
+}
+
+fn community_json(identifier: &str) -> String {
+    format!(
+        r#"{{"identifier":"{0}","name":"{0}","discord_url":null,"wiki_url":null,"require_package_listing_approval":false}}"#,
+        identifier
+    )
+}
+
+#[tokio::test]
+async fn stream_communities_follows_cursor() -> Result<()> {
+    let first = page_json(
+        &[community_json("riskofrain2"), community_json("valheim")],
+        Some("2"),
+    );
+    let second = page_json(&[community_json("lethal-company")], None);
+
+    let client = mock_client(&[
+        ("/api/experimental/community/", &first),
+        ("/api/experimental/community/?cursor=2", &second),
+    ])
+    .await;
+
+    let communities: Vec<_> = client.stream_communities().try_collect().await?;
+    let identifiers: Vec<_> = communities.iter().map(|c| c.identifier.as_str()).collect();
+    assert_eq!(identifiers, ["riskofrain2", "valheim", "lethal-company"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream_search_follows_cursor() -> Result<()> {
+    let first = page_json(
+        &[package_json("A-Emotes"), package_json("B-Emotes")],
+        Some("2"),
+    );
+    let second = page_json(&[package_json("C-Emotes")], None);
+
+    let client = mock_client(&[
+        (
+            "/api/experimental/community/valheim/package/?q=emotes&ordering=newest",
+            &first,
+        ),
+        (
+            "/api/experimental/community/valheim/package/?q=emotes&ordering=newest&cursor=2",
+            &second,
+        ),
+    ])
+    .await;
+
+    let packages: Vec<_> = client
+        .stream_search("valheim", "emotes", models::PackageOrdering::Newest)
+        .try_collect()
+        .await?;
+    let names: Vec<_> = packages.iter().map(|p| p.full_name.as_str()).collect();
+    assert_eq!(names, ["A-Emotes", "B-Emotes", "C-Emotes"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_communities_returns_page() -> Result<()> {
+    let first = page_json(&[community_json("valheim")], Some("2"));
+    let client = mock_client(&[("/api/experimental/community/", &first)]).await;
+
+    let page = client.get_communities(None).await?;
+    assert_eq!(page.items.len(), 1);
+    assert!(page.has_next());
+    assert!(!page.has_prev());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn next_and_prev_communities_follow_cursor() -> Result<()> {
+    let first = page_json(&[community_json("valheim")], Some("2"));
+    let second = page_json(&[community_json("lethal-company")], None);
+
+    let client = mock_client(&[
+        ("/api/experimental/community/", &first),
+        ("/api/experimental/community/?cursor=2", &second),
+    ])
+    .await;
+
+    let page = client.get_communities(None).await?;
+    assert!(client.prev_communities(&page.cursor).await?.is_none());
+
+    let next = client.next_communities(&page.cursor).await?.unwrap();
+    assert_eq!(next.items[0].identifier, "lethal-company");
+    assert!(client.next_communities(&next.cursor).await?.is_none());
+
+    Ok(())
+}
+
+fn category_json(slug: &str) -> String {
+    format!(r#"{{"name":"{0}","slug":"{0}"}}"#, slug)
+}
+
+#[tokio::test]
+async fn stream_categories_drains_all_pages() -> Result<()> {
+    let first = page_json(&[category_json("mods"), category_json("tools")], Some("2"));
+    let second = page_json(&[category_json("audio")], Some("3"));
+    let third = page_json(&[category_json("modpacks")], None);
+
+    let client = mock_client(&[
+        ("/api/experimental/community/valheim/category/", &first),
+        (
+            "/api/experimental/community/valheim/category/?cursor=2",
+            &second,
+        ),
+        (
+            "/api/experimental/community/valheim/category/?cursor=3",
+            &third,
+        ),
+    ])
+    .await;
+
+    let categories: Vec<_> = client.stream_categories("valheim").try_collect().await?;
+    let slugs: Vec<_> = categories.iter().map(|c| c.slug.as_str()).collect();
+    assert_eq!(slugs, ["mods", "tools", "audio", "modpacks"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_communities_collects_all_pages() -> Result<()> {
+    let first = linked_page_json(
+        &[community_json("riskofrain2")],
+        Some("/api/experimental/community/?cursor=2"),
+    );
+    let second = page_json(&[community_json("valheim")], None);
+
+    let client = mock_client(&[
+        ("/api/experimental/community/", &first),
+        ("/api/experimental/community/?cursor=2", &second),
+    ])
+    .await;
+
+    let communities = client.list_communities().await?;
+    assert_eq!(communities.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_categories_collects_all_pages() -> Result<()> {
+    let first = linked_page_json(
+        &[category_json("mods")],
+        Some("/api/experimental/community/valheim/category/?cursor=2"),
+    );
+    let second = page_json(&[category_json("tools")], None);
+
+    let client = mock_client(&[
+        ("/api/experimental/community/valheim/category/", &first),
+        (
+            "/api/experimental/community/valheim/category/?cursor=2",
+            &second,
+        ),
+    ])
+    .await;
+
+    let categories = client.list_categories("valheim").await?;
+    assert_eq!(categories.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn follow_pagination_uses_next_link() -> Result<()> {
+    // the links don't carry a `cursor`, so they can only be followed literally
+    let second = linked_page_json(&[community_json("valheim")], Some("/pages/3"));
+    let third = linked_page_json(&[community_json("lethal-company")], None);
+
+    let client = mock_client(&[("/pages/2", &second), ("/pages/3", &third)]).await;
+
+    let first = |next_path| {
+        let body = linked_page_json(&[community_json("riskofrain2")], next_path)
+            .replace(MOCK_BASE_URL, &client.base_url);
+        serde_json::from_str::<models::PaginatedResponse<models::Community>>(&body)
+    };
+
+    assert_eq!(client.follow_pagination(first(None)?).await?.len(), 1);
+
+    let communities = client.follow_pagination(first(Some("/pages/2"))?).await?;
+    let ids: Vec<_> = communities.iter().map(|c| c.identifier.as_str()).collect();
+    assert_eq!(ids, ["riskofrain2", "valheim", "lethal-company"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_community_works() -> Result<()> {
+    let client = mock_client(&[(
+        "/api/experimental/community/valheim/",
+        &community_json("valheim"),
+    )])
+    .await;
+
+    let community = client.get_community("valheim").await?;
+    assert_eq!(community.identifier, "valheim");
+
+    match client.get_community("valheim2").await {
+        Err(Error::NotFound { .. }) => (),
+        other => panic!("expected NotFound error, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+fn dependency_graph_of(
+    edges: &[(&str, &[&str])],
+) -> std::collections::HashMap<VersionId, Vec<VersionId>> {
+    edges
+        .iter()
+        .map(|(id, dependencies)| {
+            (
+                id.parse().unwrap(),
+                dependencies
+                    .iter()
+                    .map(|dep| dep.parse().unwrap())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn install_order_puts_dependencies_first() -> Result<()> {
+    let graph = dependency_graph_of(&[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0", "C-Api-1.0.0"]),
+        ("B-Lib-1.0.0", &["C-Api-1.0.0"]),
+        ("C-Api-1.0.0", &[]),
+    ]);
+
+    let order = dependencies::install_order(&"A-Mod-1.0.0".parse()?, &graph)?;
+    let order: Vec<_> = order.iter().map(VersionId::as_str).collect();
+    assert_eq!(order, ["C-Api-1.0.0", "B-Lib-1.0.0", "A-Mod-1.0.0"]);
+
+    Ok(())
+}
+
+#[test]
+fn install_order_picks_highest_version() -> Result<()> {
+    let graph = dependency_graph_of(&[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0", "C-Api-1.2.0"]),
+        ("B-Lib-1.0.0", &["C-Api-1.10.0"]),
+        ("C-Api-1.2.0", &[]),
+        ("C-Api-1.10.0", &[]),
+    ]);
+
+    let order = dependencies::install_order(&"A-Mod-1.0.0".parse()?, &graph)?;
+    let order: Vec<_> = order.iter().map(VersionId::as_str).collect();
+    assert_eq!(order, ["C-Api-1.10.0", "B-Lib-1.0.0", "A-Mod-1.0.0"]);
+
+    Ok(())
+}
+
+#[test]
+fn install_order_handles_cycles() -> Result<()> {
+    let graph = dependency_graph_of(&[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0"]),
+        ("B-Lib-1.0.0", &["A-Mod-1.0.0"]),
+    ]);
+
+    let order = dependencies::install_order(&"A-Mod-1.0.0".parse()?, &graph)?;
+    assert_eq!(order.len(), 2);
+
+    Ok(())
+}
+
+fn version_json(id: &str, dependencies: &[&str]) -> String {
+    let id: VersionId = id.parse().unwrap();
+    let dependencies: Vec<_> = dependencies
+        .iter()
+        .map(|dep| format!("\"{}\"", dep))
+        .collect();
+
+    format!(
+        r#"{{
+            "namespace": "{namespace}",
+            "name": "{name}",
+            "version_number": "{version}",
+            "full_name": "{id}",
+            "description": "",
+            "icon": "https://gcdn.thunderstore.io/live/repository/icons/{id}.png",
+            "dependencies": [{dependencies}],
+            "download_url": "https://thunderstore.io/package/download/{namespace}/{name}/{version}/",
+            "downloads": 0,
+            "date_created": "2024-01-01T00:00:00Z",
+            "website_url": "",
+            "is_active": true
+        }}"#,
+        namespace = id.namespace(),
+        name = id.name(),
+        version = id.version(),
+        id = id,
+        dependencies = dependencies.join(",")
+    )
+}
+
+fn version_route(id: &str) -> String {
+    let id: VersionId = id.parse().unwrap();
+    format!("/api/experimental/package/{}/", id.path())
+}
+
+/// Creates a mock client serving the given versions and their dependencies.
+async fn mock_versions_client(versions: &[(&str, &[&str])]) -> Client {
+    let routes: Vec<_> = versions
+        .iter()
+        .map(|(id, deps)| (version_route(id), version_json(id, deps)))
+        .collect();
+
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, body)| (path.as_str(), body.as_str()))
+        .collect();
+
+    mock_client(&routes).await
+}
+
+#[tokio::test]
+async fn resolve_dependencies_works() -> Result<()> {
+    let client = mock_versions_client(&[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0", "C-Api-1.2.0"]),
+        ("B-Lib-1.0.0", &["C-Api-1.10.0"]),
+        ("C-Api-1.2.0", &[]),
+        ("C-Api-1.10.0", &[]),
+    ])
+    .await;
+
+    let resolved = client.resolve_dependencies("A-Mod-1.0.0").await?;
+    let names: Vec<_> = resolved.iter().map(|v| v.full_name.as_str()).collect();
+    assert_eq!(names, ["C-Api-1.10.0", "B-Lib-1.0.0", "A-Mod-1.0.0"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_with_dependencies_writes_archives() -> Result<()> {
+    let versions: &[(&str, &[&str])] = &[("A-Mod-1.0.0", &["B-Lib-1.0.0"]), ("B-Lib-1.0.0", &[])];
+    let mut routes: Vec<_> = versions
+        .iter()
+        .map(|(id, deps)| (version_route(id), version_json(id, deps)))
+        .collect();
+    routes.push((
+        "/package/download/A/Mod/1.0.0/".to_owned(),
+        "mod".to_owned(),
+    ));
+    routes.push((
+        "/package/download/B/Lib/1.0.0/".to_owned(),
+        "lib".to_owned(),
+    ));
+
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, body)| (path.as_str(), body.as_str()))
+        .collect();
+    let client = mock_client(&routes).await;
+
+    let dir = std::env::temp_dir().join("thunderstore-download-with-dependencies");
+    fs::remove_dir_all(&dir).ok();
+
+    let files = client
+        .download_with_dependencies("A-Mod-1.0.0", &dir)
+        .await?;
+
+    assert_eq!(
+        files,
+        [
+            (
+                VersionId::new("B", "Lib", "1.0.0"),
+                dir.join("B-Lib-1.0.0.zip")
+            ),
+            (
+                VersionId::new("A", "Mod", "1.0.0"),
+                dir.join("A-Mod-1.0.0.zip")
+            ),
+        ]
+    );
+    assert_eq!(fs::read(dir.join("A-Mod-1.0.0.zip"))?, b"mod");
+
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn dependency_closure_size_sums_versions() -> Result<()> {
+    let versions: &[(&str, &[&str])] = &[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0", "C-Api-1.0.0"]),
+        ("B-Lib-1.0.0", &["C-Api-1.0.0"]),
+        ("C-Api-1.0.0", &[]),
+    ];
+    let mut routes: Vec<_> = versions
+        .iter()
+        .map(|(id, deps)| (version_route(id), version_json(id, deps)))
+        .collect();
+    routes.push((
+        "/package/download/A/Mod/1.0.0/".to_owned(),
+        "mod".to_owned(),
+    ));
+    routes.push((
+        "/package/download/B/Lib/1.0.0/".to_owned(),
+        "library".to_owned(),
+    ));
+    routes.push((
+        "/package/download/C/Api/1.0.0/".to_owned(),
+        "api".to_owned(),
+    ));
+
+    let routes: Vec<_> = routes
+        .iter()
+        .map(|(path, body)| (path.as_str(), body.as_str()))
+        .collect();
+    let client = mock_server(&routes).await;
+
+    // C-Api is shared, but only downloaded once
+    assert_eq!(client.dependency_closure_size("A-Mod-1.0.0").await?, 13);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dependency_closure_size_fetches_index_once() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Rejects HEAD requests and counts how often the package index is fetched.
+    #[derive(Default)]
+    struct NoHead {
+        index_requests: Arc<AtomicUsize>,
+    }
+
+    impl transport::Transport for NoHead {
+        fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            let response = match request.url().path() {
+                "/api/experimental/package/B/Mod/2.0.0/" => {
+                    version_json("B-Mod-2.0.0", &["A-Mod-1.0.0"])
+                }
+                "/api/experimental/package/A/Mod/1.0.0/" => version_json("A-Mod-1.0.0", &[]),
+                "/api/experimental/package-index/" => {
+                    self.index_requests.fetch_add(1, Ordering::SeqCst);
+                    PACKAGE_INDEX.to_owned()
+                }
+                _ => {
+                    let response = http::Response::builder().status(405).body(String::new());
+                    return Box::pin(async move { Ok(response.unwrap().into()) });
+                }
+            };
+
+            Box::pin(async move { Ok(http::Response::new(response).into()) })
+        }
+    }
+
+    let transport = NoHead::default();
+    let index_requests = transport.index_requests.clone();
+    let client = Client::builder().with_transport(transport).build()?;
+
+    assert_eq!(client.dependency_closure_size("B-Mod-2.0.0").await?, 3072);
+    assert_eq!(index_requests.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resolve_dependencies_limits_concurrency() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serves `A-Mod-1.0.0`, which depends on many libraries, and records the most
+    /// requests that were in flight at once.
+    #[derive(Default)]
+    struct Counting {
+        in_flight: Arc<AtomicUsize>,
+        max: Arc<AtomicUsize>,
+    }
+
+    impl transport::Transport for Counting {
+        fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            let libs: Vec<_> = (0..BATCH_CONCURRENCY * 3)
+                .map(|i| format!("Lib-Lib{}-1.0.0", i))
+                .collect();
+            let id = match request.url().path() {
+                "/api/experimental/package/A/Mod/1.0.0/" => "A-Mod-1.0.0".to_owned(),
+                path => {
+                    let name = path.split('/').nth(5).unwrap_or_default();
+                    format!("Lib-{}-1.0.0", name)
+                }
+            };
+            let deps: Vec<&str> = match id.as_str() {
+                "A-Mod-1.0.0" => libs.iter().map(String::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let body = version_json(&id, &deps);
+
+            let in_flight = self.in_flight.clone();
+            let max = self.max.clone();
+
+            Box::pin(async move {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                Ok(http::Response::new(body).into())
+            })
+        }
+    }
+
+    let transport = Counting::default();
+    let max = transport.max.clone();
+    let client = Client::builder().with_transport(transport).build()?;
+
+    let resolved = client.resolve_dependencies("A-Mod-1.0.0").await?;
+    assert_eq!(resolved.len(), BATCH_CONCURRENCY * 3 + 1);
+    assert!(max.load(Ordering::SeqCst) <= BATCH_CONCURRENCY);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dependency_graph_works() -> Result<()> {
+    let client = mock_versions_client(&[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0", "C-Api-1.0.0"]),
+        ("B-Lib-1.0.0", &["C-Api-1.0.0"]),
+        ("C-Api-1.0.0", &[]),
+    ])
+    .await;
+
+    let graph = client.dependency_graph("A-Mod-1.0.0").await?;
+    let root: VersionId = "A-Mod-1.0.0".parse()?;
+    let api: VersionId = "C-Api-1.0.0".parse()?;
+
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.roots(), [&root]);
+    assert_eq!(graph.dependents_of(&api).len(), 2);
+    assert!(!graph.is_cyclic());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dependency_graph_detects_cycles() -> Result<()> {
+    let client = mock_versions_client(&[
+        ("A-Mod-1.0.0", &["B-Lib-1.0.0"]),
+        ("B-Lib-1.0.0", &["C-Api-1.0.0"]),
+        ("C-Api-1.0.0", &["B-Lib-1.0.0"]),
+    ])
+    .await;
+
+    let graph = client.dependency_graph("A-Mod-1.0.0").await?;
+    assert!(graph.is_cyclic());
+
+    Ok(())
+}
+
+/// Creates an in-memory ZIP archive containing the given files.
+#[cfg(feature = "zip")]
+fn zip_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+
+    for (name, contents) in files {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(contents).unwrap();
+    }
+
+    writer.finish().unwrap().into_inner()
+}
+
+#[cfg(feature = "zip")]
+const MANIFEST_JSON: &str = r#"{
+    "name": "CoolMod",
+    "version_number": "1.2.3",
+    "website_url": "https://github.com/Kesomannen",
+    "description": "Does cool things",
+    "dependencies": ["BepInEx-BepInExPack-5.4.2100"]
+}"#;
+
+#[cfg(feature = "zip")]
+#[test]
+fn manifest_from_zip_bytes_works() -> Result<()> {
+    let manifest = format!("\u{feff}{}", MANIFEST_JSON);
+    let data = zip_bytes(&[
+        ("manifest.json", manifest.as_bytes()),
+        ("README.md", b"# CoolMod"),
+    ]);
+
+    let manifest = models::PackageManifest::from_zip_bytes(&data)?;
+    assert_eq!(manifest.name, "CoolMod");
+    assert_eq!(manifest.version_number, semver::Version::new(1, 2, 3));
+    assert_eq!(manifest.dependencies, ["BepInEx-BepInExPack-5.4.2100"]);
+
+    Ok(())
+}
+
+#[test]
+fn package_metadata_merges_community_categories() {
+    let metadata = usermedia::PackageMetadata::new("Kesomannen", ["valheim", "lethal-company"])
+        .with_categories("valheim", ["tools"])
+        .with_community_categories(HashMap::from([
+            ("valheim", vec!["audio"]),
+            ("lethal-company", vec!["items", "emotes"]),
+        ]));
+    let json = serde_json::to_value(metadata).unwrap();
+
+    assert_eq!(
+        json["community_categories"],
+        serde_json::json!({
+            "valheim": ["tools", "audio"],
+            "lethal-company": ["items", "emotes"],
+        })
+    );
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn package_metadata_from_manifest_uses_author() {
+    let mut manifest: models::PackageManifest = serde_json::from_str(MANIFEST_JSON).unwrap();
+    assert!(usermedia::PackageMetadata::from_manifest(&manifest, ["valheim"]).is_none());
+
+    manifest.author = Some("Kesomannen".to_owned());
+    let metadata = usermedia::PackageMetadata::from_manifest(&manifest, ["valheim"]).unwrap();
+    let json = serde_json::to_value(metadata).unwrap();
+
+    assert_eq!(json["author_name"], "Kesomannen");
+    assert_eq!(json["communities"], serde_json::json!(["valheim"]));
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn manifest_from_zip_bytes_fails_without_manifest() {
+    let data = zip_bytes(&[("README.md", b"# CoolMod")]);
+
+    assert!(matches!(
+        models::PackageManifest::from_zip_bytes(&data),
+        Err(Error::ManifestNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn get_icon_works() -> Result<()> {
+    let client = mock_client(&[("/icon.png", "not really a png")]).await;
+
+    let mut version: models::PackageVersion =
+        serde_json::from_str(&version_json("A-Mod-1.0.0", &[]))?;
+    version.icon = format!("{}/icon.png", client.base_url).parse().unwrap();
+
+    let icon = client.get_icon(&version).await?;
+    assert_eq!(&icon[..], b"not really a png");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_icon_fails_without_versions() -> Result<()> {
+    let client = mock_client(&[]).await;
+    let package: models::PackageV1 = serde_json::from_str(&package_v1_json("A-Mod", &[]))?;
+
+    assert_eq!(package.icon_url(), None);
+    assert!(matches!(
+        client.get_icon(&package).await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+fn package_v1_json(full_name: &str, versions: &[(&str, &str)]) -> String {
+    let id: PackageId = full_name.parse().unwrap();
+
+    let versions: Vec<_> = versions
+        .iter()
+        .enumerate()
+        .map(|(i, (version, date_created))| {
+            format!(
+                r#"{{
+                    "date_created": "{date_created}",
+                    "dependencies": [],
+                    "description": "",
+                    "download_url": "https://thunderstore.io/package/download/{namespace}/{name}/{version}/",
+                    "downloads": 10,
+                    "file_size": 1024,
+                    "full_name": "{full_name}-{version}",
+                    "icon": "https://gcdn.thunderstore.io/live/repository/icons/{full_name}-{version}.png",
+                    "is_active": true,
+                    "name": "{name}",
+                    "uuid4": "00000000-0000-0000-0000-{i:012}",
+                    "version_number": "{version}",
+                    "website_url": ""
+                }}"#,
+                namespace = id.namespace(),
+                name = id.name(),
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{
+            "categories": ["Mods"],
+            "date_created": "2024-01-01T00:00:00Z",
+            "date_updated": "2024-01-01T00:00:00Z",
+            "donation_link": null,
+            "full_name": "{full_name}",
+            "has_nsfw_content": false,
+            "is_deprecated": false,
+            "is_pinned": false,
+            "name": "{name}",
+            "owner": "{namespace}",
+            "package_url": "https://thunderstore.io/c/valheim/p/{namespace}/{name}/",
+            "rating_score": 5,
+            "uuid4": "10000000-0000-0000-0000-000000000000",
+            "versions": [{versions}]
+        }}"#,
+        namespace = id.namespace(),
+        name = id.name(),
+        versions = versions.join(",")
+    )
+}
+
+fn package_v1(full_name: &str, versions: &[(&str, &str)]) -> models::PackageV1 {
+    serde_json::from_str(&package_v1_json(full_name, versions)).unwrap()
+}
+
+#[test]
+fn matching_version_picks_highest_match() {
+    let package = package_v1(
+        "A-Mod",
+        &[
+            ("2.0.0", "2024-04-01T00:00:00Z"),
+            ("1.10.0", "2024-03-01T00:00:00Z"),
+            ("1.2.0", "2024-02-01T00:00:00Z"),
+            ("1.0.0", "2024-01-01T00:00:00Z"),
+        ],
+    );
+
+    let req = semver::VersionReq::parse("^1.2").unwrap();
+    let version = package.matching_version(&req).unwrap();
+    assert_eq!(version.version_number, semver::Version::new(1, 10, 0));
+
+    let req = semver::VersionReq::parse("^3").unwrap();
+    assert!(package.matching_version(&req).is_none());
+}
+
+#[test]
+fn package_summaries_match() {
+    let package: models::Package = serde_json::from_str(&package_json("A-Mod")).unwrap();
+    let package_v1 = package_v1(
+        "A-Mod",
+        &[
+            ("2.0.0", "2024-02-01T00:00:00Z"),
+            ("1.0.0", "2024-01-01T00:00:00Z"),
+        ],
+    );
+
+    let a = models::PackageSummary::from(&package);
+    let b = models::PackageSummary::from(&package_v1);
+
+    assert_eq!((a.namespace.as_str(), a.name.as_str()), ("A", "Mod"));
+    assert_eq!((b.namespace.as_str(), b.name.as_str()), ("A", "Mod"));
+    assert_eq!(a.full_name, b.full_name);
+    assert_eq!(
+        b.total_downloads,
+        package_v1
+            .versions
+            .iter()
+            .map(|v| u64::from(v.downloads))
+            .sum::<u64>()
+    );
+}
+
+#[test]
+fn get_version_with_id_works() {
+    let package = package_v1(
+        "A-Mod",
+        &[
+            ("2.0.0", "2024-02-01T00:00:00Z"),
+            ("1.0.0", "2024-01-01T00:00:00Z"),
+        ],
+    );
+
+    let version = package
+        .get_version_with_id(&VersionId::new("A", "Mod", "1.0.0"))
+        .unwrap();
+    assert_eq!(version.version_number, semver::Version::new(1, 0, 0));
+
+    assert!(package
+        .get_version_with_id(&VersionId::new("B", "Mod", "1.0.0"))
+        .is_none());
+}
+
+#[test]
+fn versions_by_date_ignores_api_order() {
+    let package = package_v1(
+        "A-Mod",
+        &[
+            ("1.0.0", "2024-01-01T00:00:00Z"),
+            ("1.2.0", "2024-03-01T00:00:00Z"),
+            ("1.1.0", "2024-02-01T00:00:00Z"),
+        ],
+    );
+
+    let versions: Vec<_> = package
+        .versions_by_date()
+        .iter()
+        .map(|v| v.version_number.to_string())
+        .collect();
+
+    assert_eq!(versions, ["1.2.0", "1.1.0", "1.0.0"]);
+    assert_eq!(
+        package.newest_version().unwrap().version_number,
+        semver::Version::new(1, 2, 0)
+    );
+}
+
+#[test]
+fn id_from_url_works() -> Result<()> {
+    let package_url = "https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/"
+        .parse()
+        .unwrap();
+    let version_url = "https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/v/0.16.0/"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        PackageId::from_url(&package_url)?,
+        PackageId::new("Evaisa", "LethalLib")
+    );
+    assert_eq!(
+        PackageId::from_url(&version_url)?,
+        PackageId::new("Evaisa", "LethalLib")
+    );
+    assert_eq!(
+        VersionId::from_url(&version_url)?,
+        VersionId::new("Evaisa", "LethalLib", "0.16.0")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn id_from_url_fails_on_other_urls() {
+    let community_url = "https://thunderstore.io/c/lethal-company/".parse().unwrap();
+    let package_url = "https://thunderstore.io/c/lethal-company/p/Evaisa/LethalLib/"
+        .parse()
+        .unwrap();
+
+    assert!(PackageId::from_url(&community_url).is_err());
+    assert!(VersionId::from_url(&package_url).is_err());
+}
+
+#[tokio::test]
+async fn client_errors_include_body() -> Result<()> {
+    let client = mock_client_with_status(&[(
+        "/api/experimental/community/valheim/",
+        400,
+        r#"{"detail":"Bad request"}"#,
+    )])
+    .await;
+
+    match client.get_community("valheim").await {
+        Err(Error::Client { status, body }) => {
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body, r#"{"detail":"Bad request"}"#);
+        }
+        other => panic!("expected Client error, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn not_found_includes_resource() -> Result<()> {
+    let client = mock_client(&[]).await;
+
+    match client.get_community("valheim").await {
+        Err(Error::NotFound { resource }) => {
+            assert_eq!(
+                resource.as_deref(),
+                Some("/api/experimental/community/valheim/")
+            );
+        }
+        other => panic!("expected NotFound error, got {:?}", other),
+    }
+
+    let err = Error::NotFound { resource: None };
+    assert_eq!(err.to_string(), "Requested resource was not found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn server_errors_include_body() -> Result<()> {
+    let client = mock_client_with_status(&[(
+        "/api/experimental/community/valheim/",
+        503,
+        "Service unavailable",
+    )])
+    .await;
+
+    match client.get_community("valheim").await {
+        Err(Error::Server { status, body }) => {
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(body, "Service unavailable");
+        }
+        other => panic!("expected Server error, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn is_transient_only_matches_retryable_errors() {
+    let server = Error::Server {
+        status: StatusCode::SERVICE_UNAVAILABLE,
+        body: String::new(),
+    };
+    let client = Error::Client {
+        status: StatusCode::BAD_REQUEST,
+        body: String::new(),
+    };
+    let builder = Error::from(reqwest::Client::new().get("not a url").build().unwrap_err());
+
+    assert!(server.is_transient());
+    assert!(!client.is_transient());
+    assert!(!builder.is_transient());
+    assert!(!Error::NotFound { resource: None }.is_transient());
+}
+
+#[tokio::test]
+async fn validate_icon_reports_errors() -> Result<()> {
+    let mut client = mock_client_with_status(&[(
+        "/api/experimental/submission/validate/icon/",
+        400,
+        r#"{"icon_data":["Invalid image dimensions, must be 256x256"]}"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let outcome = client.validate_icon("Kesomannen", b"not a png").await?;
+    assert!(!outcome.is_valid());
+    assert_eq!(
+        outcome.messages().collect::<Vec<_>>(),
+        ["Invalid image dimensions, must be 256x256"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn validate_readme_succeeds() -> Result<()> {
+    let mut client = mock_client(&[(
+        "/api/experimental/submission/validate/readme/",
+        r#"{"success":true}"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    assert!(client.validate_readme("# CoolMod").await?.is_valid());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn validate_readme_fails_without_reason() -> Result<()> {
+    let mut client = mock_client(&[(
+        "/api/experimental/submission/validate/readme/",
+        r#"{"success":false}"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let outcome = client.validate_readme("# CoolMod").await?;
+    assert!(!outcome.is_valid());
+    assert!(outcome.failed_without_reason);
+    assert_eq!(outcome.messages().count(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn validate_package_aggregates_outcomes() -> Result<()> {
+    let mut client = mock_client_with_status(&[
+        (
+            "/api/experimental/submission/validate/manifest-v1/",
+            200,
+            r#"{"success":true}"#,
+        ),
+        (
+            "/api/experimental/submission/validate/readme/",
+            200,
+            r#"{"success":true}"#,
+        ),
+        (
+            "/api/experimental/submission/validate/icon/",
+            400,
+            r#"{"icon_data":["Invalid image dimensions, must be 256x256"]}"#,
+        ),
+    ])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let validation = client
+        .validate_package("Kesomannen", "{}", "# CoolMod", b"not a png")
+        .await?;
+
+    assert!(!validation.is_valid());
+    assert!(validation.manifest.is_valid());
+    assert!(validation.readme.is_valid());
+    assert_eq!(
+        validation.messages().collect::<Vec<_>>(),
+        ["Invalid image dimensions, must be 256x256"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn verify_token_works() -> Result<()> {
+    let mut client = mock_client(&[(
+        "/api/experimental/current-user/",
+        r#"{"username":"Kesomannen","capabilities":[],"ratedPackages":[],"teams":[]}"#,
+    )])
+    .await;
+    assert!(!client.verify_token().await?);
+
+    client.token = Some("tss_XXX".to_owned());
+    assert!(client.verify_token().await?);
+
+    let mut client = mock_client_with_status(&[("/api/experimental/current-user/", 401, "")]).await;
+    client.token = Some("tss_XXX".to_owned());
+    assert!(!client.verify_token().await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn verify_token_rejects_anonymous_user() -> Result<()> {
+    let mut client = mock_client(&[(
+        "/api/experimental/current-user/",
+        r#"{"username":null,"capabilities":[],"ratedPackages":[],"teams":[]}"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    assert!(!client.verify_token().await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_current_user_works() -> Result<()> {
+    let mut client = mock_client(&[(
+        "/api/experimental/current-user/",
+        r#"{"username":"Kesomannen","capabilities":["package.rate"],"ratedPackages":["A-Mod"],"subscription":{"expires":null},"teams":["Kesomannen","Team"]}"#,
+    )])
+    .await;
+
+    assert!(matches!(
+        client.get_current_user().await,
+        Err(Error::ApiTokenRequired)
+    ));
+
+    client.token = Some("tss_XXX".to_owned());
+    let user = client.get_current_user().await?;
+    assert_eq!(user.username, "Kesomannen");
+    assert_eq!(user.teams, ["Kesomannen", "Team"]);
+    assert_eq!(user.rated_packages, ["A-Mod"]);
+    assert!(user.capabilities.contains("package.rate"));
+
+    let mut client = mock_client(&[(
+        "/api/experimental/current-user/",
+        r#"{"username":null,"capabilities":[],"ratedPackages":[],"teams":[]}"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+    assert!(matches!(
+        client.get_current_user().await,
+        Err(Error::ApiTokenInvalid)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn validate_requires_token() {
+    let client = mock_client(&[]).await;
+
+    assert!(matches!(
+        client.validate_readme("# CoolMod").await,
+        Err(Error::ApiTokenRequired)
+    ));
+}
+
+#[tokio::test]
+async fn submit_package_returns_field_errors() {
+    let mut client = mock_client_with_status(&[(
+        "/api/experimental/submission/submit/",
+        400,
+        r#"{"communities":["Object with slug=notacommunity does not exist."]}"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let metadata = usermedia::PackageMetadata::new("Kesomannen", ["notacommunity"]);
+
+    match client.submit_package(uuid::Uuid::nil(), metadata).await {
+        Err(Error::Submission(err)) => assert_eq!(
+            err.field_errors["communities"],
+            ["Object with slug=notacommunity does not exist."]
+        ),
+        other => panic!("expected submission error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn request_hook_is_applied() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_clone = calls.clone();
+
+    let mut client = mock_client(&[(
+        "/api/experimental/community/valheim/",
+        &community_json("valheim"),
+    )])
+    .await;
+
+    client.request_hook = Client::builder()
+        .with_request_hook(move |request| {
+            calls_clone.fetch_add(1, Ordering::SeqCst);
+            request.header("X-Test", "1")
+        })
+        .request_hook;
+
+    client.get_community("valheim").await?;
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn metrics_hook_reports_status() -> Result<()> {
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let calls_clone = calls.clone();
+
+    let mut client = mock_client(&[(
+        "/api/experimental/community/valheim/",
+        &community_json("valheim"),
+    )])
+    .await;
+
+    client.metrics_hook = Client::builder()
+        .with_metrics_hook(move |path, _, status| {
+            calls_clone.lock().unwrap().push((path.to_owned(), status));
+        })
+        .metrics_hook;
+
+    client.get_community("valheim").await?;
+    client.get_community("lethal-company").await.ok();
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            (
+                "/api/experimental/community/valheim/".to_owned(),
+                Some(StatusCode::OK)
+            ),
+            (
+                "/api/experimental/community/lethal-company/".to_owned(),
+                Some(StatusCode::NOT_FOUND)
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn headers_are_sent_with_requests() -> Result<()> {
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen_clone = seen.clone();
+
+    let transport = MockTransport::new().with_response(
+        "/api/experimental/community/valheim/",
+        community_json("valheim"),
+    );
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-api-key", "old".parse().unwrap());
+    headers.insert("x-other", "other".parse().unwrap());
+
+    let client = Client::builder()
+        .with_transport(transport)
+        .with_headers(headers)
+        .with_header("X-Api-Key", "secret")
+        .with_request_hook(move |request| {
+            let built = request.try_clone().unwrap().build().unwrap();
+            seen_clone.lock().unwrap().push(built.headers().clone());
+            request
+        })
+        .build()?;
+
+    client.get_community("valheim").await?;
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0]["x-api-key"], "secret");
+    assert_eq!(seen[0]["x-other"], "other");
+
+    assert!(matches!(
+        Client::builder().with_header("Bad Name", "value").build(),
+        Err(Error::InvalidHeader(name)) if name == "Bad Name"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn token_from_env_works() -> Result<()> {
+    std::env::set_var("THUNDERSTORE_TEST_TOKEN_SET", "tss_XXX");
+    std::env::set_var("THUNDERSTORE_TEST_TOKEN_EMPTY", "");
+
+    let client = Client::builder()
+        .with_token_from_env("THUNDERSTORE_TEST_TOKEN_SET")
+        .build()?;
+    assert_eq!(client.token.as_deref(), Some("tss_XXX"));
+
+    for var in [
+        "THUNDERSTORE_TEST_TOKEN_EMPTY",
+        "THUNDERSTORE_TEST_TOKEN_UNSET",
+    ] {
+        let result = Client::builder().with_token_from_env(var).build();
+        assert!(matches!(result, Err(Error::MissingEnvVar(_))));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn package_exists_works() -> Result<()> {
+    let client = mock_client_with_status(&[
+        (
+            "/api/experimental/package/Kesomannen/GaleModManager/",
+            200,
+            "",
+        ),
+        (
+            "/api/experimental/package/Kesomannen/GaleModManager/0.6.0/",
+            200,
+            "",
+        ),
+        ("/api/experimental/package/Kesomannen/Broken/", 500, ""),
+    ])
+    .await;
+
+    assert!(client.package_exists("Kesomannen-GaleModManager").await?);
+    assert!(!client.package_exists("Kesomannen-GaleModManager2").await?);
+    assert!(
+        client
+            .version_exists("Kesomannen-GaleModManager-0.6.0")
+            .await?
+    );
+    assert!(
+        !client
+            .version_exists("Kesomannen-GaleModManager-0.6.1")
+            .await?
+    );
+    assert!(client.package_exists("Kesomannen-Broken").await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_packages_by_namespace_filters_owner() -> Result<()> {
+    // the listing is compacted since the v1 stream splits packages on `}]},`
+    let packages: Vec<serde_json::Value> = [
+        "Kesomannen-GaleModManager",
+        "Other-Mod",
+        "kesomannen-Lib",
+        "Other-Lib",
+    ]
+    .iter()
+    .map(|name| {
+        serde_json::from_str(&package_v1_json(name, &[("1.0.0", "2024-01-01T00:00:00Z")])).unwrap()
+    })
+    .collect();
+    let listing = serde_json::to_string(&packages).unwrap();
+
+    let client = mock_client(&[("/c/valheim/api/v1/package/", &listing)]).await;
+
+    let packages = client
+        .list_packages_by_namespace("valheim", "Kesomannen")
+        .await?;
+    let names: Vec<_> = packages
+        .iter()
+        .map(|package| package.full_name.as_str())
+        .collect();
+
+    assert_eq!(names, ["Kesomannen-GaleModManager", "kesomannen-Lib"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_all_download_urls_maps_versions() -> Result<()> {
+    let listing = format!(
+        "[{},{}]",
+        package_v1_json("Other-Mod", &[("1.0.0", "2024-01-01T00:00:00Z")]),
+        package_v1_json(
+            "A-Mod",
+            &[
+                ("2.0.0", "2024-02-01T00:00:00Z"),
+                ("1.0.0", "2024-01-01T00:00:00Z"),
+            ],
+        )
+    );
+
+    let client = mock_client(&[("/c/valheim/api/v1/package/", &listing)]).await;
+
+    let urls = client.get_all_download_urls("valheim", "A-Mod").await?;
+    let urls: Vec<_> = urls
+        .iter()
+        .map(|(id, url)| (id.as_str(), url.as_str()))
+        .collect();
+
+    assert_eq!(
+        urls,
+        [
+            (
+                "A-Mod-2.0.0",
+                "https://thunderstore.io/package/download/A/Mod/2.0.0/"
+            ),
+            (
+                "A-Mod-1.0.0",
+                "https://thunderstore.io/package/download/A/Mod/1.0.0/"
+            ),
+        ]
+    );
+
+    assert!(matches!(
+        client.get_all_download_urls("valheim", "B-Mod").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn default_community_is_used() -> Result<()> {
+    let listing = format!(
+        "[{}]",
+        package_v1_json("A-Mod", &[("1.0.0", "2024-01-01T00:00:00Z")])
+    );
+
+    let mut client = mock_client(&[("/c/valheim/api/v1/package/", &listing)]).await;
+
+    assert!(matches!(
+        client.list_packages().await,
+        Err(Error::NoCommunitySet)
+    ));
+
+    client.default_community = Some("valheim".parse()?);
+    assert_eq!(client.list_packages().await?.len(), 1);
+
+    let packages: Vec<_> = client.stream_packages().await?.try_collect().await?;
+    assert_eq!(packages.len(), 1);
+
+    assert!(matches!(
+        Client::builder()
+            .with_default_community("Not A Slug")
+            .build(),
+        Err(Error::InvalidCommunitySlug(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_updates_compares_versions() -> Result<()> {
+    let client = mock_client(&[
+        (
+            "/c/valheim/api/v1/package-metrics/A/Mod/",
+            r#"{"downloads":10,"rating_score":1,"latest_version":"1.2.0"}"#,
+        ),
+        (
+            "/c/valheim/api/v1/package-metrics/C/Mod/",
+            r#"{"downloads":30,"rating_score":3,"latest_version":"2.0.0"}"#,
+        ),
+    ])
+    .await;
+
+    let updates = client
+        .check_updates("valheim", ["A-Mod-1.0.0", "B-Mod-1.0.0", "C-Mod-2.0.0"])
+        .await?;
+
+    assert_eq!(
+        updates,
+        [
+            models::UpdateInfo {
+                current: VersionId::new("A", "Mod", "1.0.0"),
+                latest: VersionId::new("A", "Mod", "1.2.0"),
+                update_available: true,
+            },
+            models::UpdateInfo {
+                current: VersionId::new("C", "Mod", "2.0.0"),
+                latest: VersionId::new("C", "Mod", "2.0.0"),
+                update_available: false,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_metrics_batch_reports_per_package() -> Result<()> {
+    let client = mock_client(&[
+        (
+            "/c/valheim/api/v1/package-metrics/A/Mod/",
+            r#"{"downloads":10,"rating_score":1,"latest_version":"1.0.0"}"#,
+        ),
+        (
+            "/c/valheim/api/v1/package-metrics/C/Mod/",
+            r#"{"downloads":30,"rating_score":3,"latest_version":"1.0.0"}"#,
+        ),
+    ])
+    .await;
+
+    let results = client
+        .get_metrics_batch("valheim", ["A-Mod", "B-Mod", "C-Mod"])
+        .await?;
+
+    let ids: Vec<_> = results.iter().map(|(id, _)| id.to_string()).collect();
+    assert_eq!(ids, ["A-Mod", "B-Mod", "C-Mod"]);
+
+    assert_eq!(results[0].1.as_ref().unwrap().downloads, 10);
+    assert!(matches!(results[1].1, Err(Error::NotFound { .. })));
+    assert_eq!(results[2].1.as_ref().unwrap().downloads, 30);
+
+    assert!(client
+        .get_metrics_batch("valheim", ["A-Mod", "invalid"])
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+fn listed_wiki_json(name: &str) -> String {
+    format!(
+        r#"{{
+            "namespace": "Kesomannen",
+            "name": "{name}",
+            "wiki": {{
+                "id": "1",
+                "title": "{name}",
+                "slug": "{name}",
+                "datetime_created": "2024-01-01T00:00:00Z",
+                "datetime_updated": "2024-01-01T00:00:00Z",
+                "pages": []
+            }}
+        }}"#
+    )
+}
+
+fn wikis_json(names: &[&str], cursor: &str, has_more: bool) -> String {
+    let results: Vec<_> = names.iter().map(|name| listed_wiki_json(name)).collect();
+    format!(
+        r#"{{"results":[{}],"cursor":"{cursor}","has_more":{has_more}}}"#,
+        results.join(",")
+    )
+}
+
+#[tokio::test]
+async fn stream_wikis_stops_when_no_more() -> Result<()> {
+    let first = wikis_json(&["A", "B"], "2024-01-02T00:00:00Z", true);
+    let second = wikis_json(&["C"], "2024-01-03T00:00:00Z", false);
+
+    let client = mock_client(&[
+        ("/api/experimental/package/wikis/", &first),
+        (
+            "/api/experimental/package/wikis/?after=2024-01-02T00%3A00%3A00Z",
+            &second,
+        ),
+    ])
+    .await;
+
+    let wikis: Vec<_> = client.stream_wikis().try_collect().await?;
+    let names: Vec<_> = wikis.iter().map(|wiki| wiki.name.as_str()).collect();
+
+    assert_eq!(names, ["A", "B", "C"]);
+
+    Ok(())
+}
+
+fn wiki_page_json(content: &str) -> String {
+    format!(
+        r#"{{
+            "id": "1",
+            "title": "Home",
+            "slug": "home",
+            "datetime_created": "2024-01-01T00:00:00Z",
+            "datetime_updated": "2024-01-01T00:00:00Z",
+            "markdown_content": "{content}"
+        }}"#
+    )
+}
+
+#[tokio::test]
+async fn get_wiki_titles_lists_pages() -> Result<()> {
+    let wiki = r#"{
+        "id": "1",
+        "title": "Mod",
+        "slug": "mod",
+        "datetime_created": "2024-01-01T00:00:00Z",
+        "datetime_updated": "2024-01-01T00:00:00Z",
+        "pages": [
+            {"id": "10", "title": "Home", "slug": "home", "datetime_created": "2024-01-01T00:00:00Z", "datetime_updated": "2024-01-01T00:00:00Z"},
+            {"id": "11", "title": "Config", "slug": "config", "datetime_created": "2024-01-01T00:00:00Z", "datetime_updated": "2024-01-01T00:00:00Z"}
+        ]
+    }"#;
+
+    let client = mock_client(&[("/api/experimental/package/A/Mod/wiki/", wiki)]).await;
+
+    assert_eq!(
+        client.get_wiki_titles("A-Mod").await?,
+        [
+            ("10".to_owned(), "Home".to_owned()),
+            ("11".to_owned(), "Config".to_owned())
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_wiki_page_reports_errors() -> Result<()> {
+    let mut client = mock_client_with_status(&[
+        ("/api/experimental/package/A/Mod/wiki/", 204, ""),
+        (
+            "/api/experimental/package/B/Mod/wiki/",
+            401,
+            r#"{"detail":"Invalid token."}"#,
+        ),
+        (
+            "/api/experimental/package/D/Mod/wiki/",
+            403,
+            r#"{"detail":"You do not have permission to perform this action."}"#,
+        ),
+    ])
+    .await;
+
+    assert!(matches!(
+        client.delete_wiki_page("A-Mod", "10").await,
+        Err(Error::ApiTokenRequired)
+    ));
+
+    client.token = Some("tss_XXX".to_owned());
+    assert_eq!(client.delete_wiki_page("A-Mod", "10").await?, "10");
+
+    assert!(matches!(
+        client.delete_wiki_page("B-Mod", "10").await,
+        Err(Error::ApiTokenInvalid)
+    ));
+    assert!(matches!(
+        client.delete_wiki_page("C-Mod", "10").await,
+        Err(Error::NotFound { .. })
+    ));
+    assert!(matches!(
+        client.delete_wiki_page("D-Mod", "10").await,
+        Err(Error::Client { status, .. }) if status == StatusCode::FORBIDDEN
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_wiki_page_html_works() -> Result<()> {
+    let page = wiki_page_json("# Home");
+    let empty_page = wiki_page_json(" ");
+
+    let client = mock_client(&[
+        ("/api/experimental/wiki/page/1/", &page),
+        ("/api/experimental/wiki/page/2/", &empty_page),
+        (
+            "/api/experimental/frontend/render-markdown/",
+            r#"{"html":"<h1>Home</h1>"}"#,
+        ),
+    ])
+    .await;
+
+    let (page, html) = client.get_wiki_page_html(1).await?;
+    assert_eq!(page.title, "Home");
+    assert_eq!(html, "<h1>Home</h1>");
+
+    assert!(matches!(
+        client.get_wiki_page_html(2).await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+const PACKAGE_INDEX: &str = r#"{"namespace":"A","name":"Mod","version_number":"1.0.0","file_format":"zip","file_size":1024,"dependencies":[]}
+{"namespace":"B","name":"Mod","version_number":"2.0.0","file_format":"zip","file_size":2048,"dependencies":["A-Mod-1.0.0"]}"#;
+
+#[tokio::test]
+async fn package_index_cache_respects_ttl() -> Result<()> {
+    let mut client = mock_server(&[("/api/experimental/package-index/", PACKAGE_INDEX)]).await;
+    let base_url = client.base_url.clone();
+
+    client.index_cache = Some(Arc::new(IndexCache::new(Duration::from_secs(60))));
+    assert_eq!(client.get_package_index_cached().await?.len(), 2);
+
+    // the cached index is used while the server is unreachable
+    client.base_url = "http://127.0.0.1:1".to_owned();
+    assert_eq!(client.get_package_index_cached().await?.len(), 2);
+
+    client.base_url = base_url;
+    client.index_cache = Some(Arc::new(IndexCache::new(Duration::ZERO)));
+    client.get_package_index_cached().await?;
+
+    client.base_url = "http://127.0.0.1:1".to_owned();
+    assert!(client.get_package_index_cached().await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cloned_client_shares_index_cache() -> Result<()> {
+    let mut client = mock_client(&[("/api/experimental/package-index/", PACKAGE_INDEX)]).await;
+    client.index_cache = Some(Arc::new(IndexCache::new(Duration::from_secs(60))));
+
+    let mut clone = client.clone();
+    client.get_package_index_cached().await?;
+
+    clone.base_url = "http://127.0.0.1:1".to_owned();
+    assert_eq!(clone.get_package_index_cached().await?.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_version_metrics_covers_all_versions() -> Result<()> {
+    let package = package_v1(
+        "A-Mod",
+        &[
+            ("1.0.1", "2024-02-01T00:00:00Z"),
+            ("1.0.0", "2024-01-01T00:00:00Z"),
+        ],
+    );
+
+    let client = mock_client(&[
+        (
+            "/c/valheim/api/v1/package-metrics/A/Mod/1.0.1/",
+            r#"{"downloads":5}"#,
+        ),
+        (
+            "/c/valheim/api/v1/package-metrics/A/Mod/1.0.0/",
+            r#"{"downloads":20}"#,
+        ),
+    ])
+    .await;
+
+    let metrics = client.get_version_metrics("valheim", &package).await?;
+
+    assert_eq!(metrics.len(), 2);
+    assert_eq!(metrics[&"A-Mod-1.0.1".parse::<VersionId>()?], 5);
+    assert_eq!(metrics[&"A-Mod-1.0.0".parse::<VersionId>()?], 20);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_to_file_streams_body() -> Result<()> {
+    let client = mock_client(&[("/package/download/A/Mod/1.0.0/", "abcd")]).await;
+
+    let stream = client.download_stream("A-Mod-1.0.0").await?;
+    let chunks: Vec<Bytes> = stream.try_collect().await?;
+    assert_eq!(chunks.concat(), b"abcd");
+
+    let path = std::env::temp_dir().join("thunderstore-download-to-file.zip");
+    client.download_to_file("A-Mod-1.0.0", &path).await?;
+    assert_eq!(fs::read(&path)?, b"abcd");
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn download_url_for_works() -> Result<()> {
+    let client = Client::builder()
+        .with_base_url("https://example.com")
+        .with_base_path("thunderstore")
+        .build()?;
+
+    assert_eq!(
+        client.download_url_for(("A", "Mod", "1.0.0"))?,
+        "https://example.com/thunderstore/package/download/A/Mod/1.0.0/"
+    );
+    assert!(client.download_url_for("A-Mod").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn version_file_size_reads_content_length() -> Result<()> {
+    let client = mock_server(&[("/package/download/A/Mod/1.0.0/", "abcd")]).await;
+
+    assert_eq!(client.version_file_size("A-Mod-1.0.0").await?, 4);
+    assert!(matches!(
+        client.version_file_size("A-Mod-2.0.0").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn version_file_size_falls_back_to_index() -> Result<()> {
+    let client = mock_client_with_status(&[
+        ("/package/download/B/Mod/2.0.0/", 405, ""),
+        ("/package/download/C/Mod/1.0.0/", 405, ""),
+        ("/api/experimental/package-index/", 200, PACKAGE_INDEX),
+    ])
+    .await;
+
+    assert_eq!(client.version_file_size("B-Mod-2.0.0").await?, 2048);
+    assert!(matches!(
+        client.version_file_size("C-Mod-1.0.0").await,
+        Err(Error::UnknownFileSize)
+    ));
+
+    Ok(())
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn extract_zip_writes_files() -> Result<()> {
+    let dir = std::env::temp_dir().join("thunderstore-extract-writes");
+    fs::remove_dir_all(&dir).ok();
+    let data = zip_bytes(&[
+        ("manifest.json", MANIFEST_JSON.as_bytes()),
+        ("plugins/CoolMod.dll", b"dll"),
+    ]);
+
+    let paths = extract_zip(&data, &dir)?;
+
+    assert_eq!(
+        paths,
+        [dir.join("manifest.json"), dir.join("plugins/CoolMod.dll")]
+    );
+    assert_eq!(fs::read(dir.join("plugins/CoolMod.dll"))?, b"dll");
+
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn extract_zip_rejects_path_traversal() {
+    let dir = std::env::temp_dir().join("thunderstore-extract-traversal");
+    fs::remove_dir_all(&dir).ok();
+    let data = zip_bytes(&[("README.md", b"# CoolMod"), ("../evil.dll", b"dll")]);
+
+    assert!(matches!(
+        extract_zip(&data, &dir),
+        Err(Error::UnsafeArchivePath(path)) if path == "../evil.dll"
+    ));
+    assert!(!dir.exists());
+}
+
+#[cfg(feature = "zip")]
+const EXPORT_R2X: &str = "profileName: Default
+mods:
+  - name: BepInEx-BepInExPack
+    version:
+      major: 5
+      minor: 4
+      patch: 2100
+    enabled: true
+  - name: Kesomannen-CoolMod
+    version:
+      major: 1
+      minor: 0
+      patch: 0
+    enabled: false
+";
+
+#[cfg(feature = "zip")]
+#[tokio::test]
+async fn get_profile_manifest_works() -> Result<()> {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+
+    let key = uuid::Uuid::nil();
+    let data = zip_bytes(&[
+        ("export.r2x", EXPORT_R2X.as_bytes()),
+        ("config/BepInEx.cfg", b"[Logging]"),
+    ]);
+    let body = format!("#r2modman\n{}", BASE64_STANDARD.encode(data));
+    let route = format!("/api/experimental/legacyprofile/get/{}/", key);
+
+    let client = mock_client(&[(&route, &body)]).await;
+    let manifest = client.get_profile_manifest(key).await?;
+
+    assert_eq!(manifest.profile_name, "Default");
+    assert_eq!(manifest.mods.len(), 2);
+    assert_eq!(
+        manifest.mods[0].version_id()?,
+        VersionId::new("BepInEx", "BepInExPack", "5.4.2100")
+    );
+    assert!(!manifest.mods[1].enabled);
+
+    Ok(())
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn profile_builder_round_trips() -> Result<()> {
+    let data = models::ProfileBuilder::new("Modded")
+        .add_mod(VersionId::new("BepInEx", "BepInExPack", "5.4.2100"), true)
+        .add_mod(VersionId::new("Kesomannen", "CoolMod", "1.0.0"), false)
+        .build()?;
+
+    let manifest = models::ProfileManifest::from_zip_bytes(&data)?;
+    let expected: models::ProfileManifest =
+        serde_yaml::from_str(&EXPORT_R2X.replace("Default", "Modded"))?;
+
+    assert_eq!(manifest, expected);
+
+    Ok(())
+}
+
+#[test]
+fn profile_builder_rejects_invalid_version() {
+    let builder = models::ProfileBuilder::new("Modded")
+        .add_mod(VersionId::new("Kesomannen", "CoolMod", "latest"), true);
+
+    assert!(matches!(builder.manifest(), Err(Error::InvalidSemver(_))));
+}
+
+#[tokio::test]
+async fn proxy_is_used_for_requests() -> Result<()> {
+    // the mock server sees the absolute URL as the request target when used as a proxy
+    let proxy = mock_server(&[(
+        "http://thunderstore.invalid/package/download/A/Mod/1.0.0/",
+        "zip",
+    )])
+    .await;
+
+    let client = Client::builder()
+        .with_base_url("http://thunderstore.invalid")
+        .with_proxy_url(&proxy.base_url)
+        .build()?;
+
+    assert_eq!(&client.download("A-Mod-1.0.0").await?[..], b"zip");
+
+    assert!(Client::builder()
+        .with_proxy_url("not a url")
+        .build()
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accept_invalid_certs_builds_working_client() -> Result<()> {
+    let mock = mock_server(&[("/package/download/A/Mod/1.0.0/", "zip")]).await;
+
+    let client = Client::builder()
+        .with_base_url(&mock.base_url)
+        .with_accept_invalid_certs(true)
+        .build()?;
+
+    assert_eq!(&client.download("A-Mod-1.0.0").await?[..], b"zip");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_package_versions_sorts_newest_first() -> Result<()> {
+    let index = r#"{"namespace":"A","name":"Mod","version_number":"1.0.0","file_format":"zip","file_size":1,"dependencies":[]}
+{"namespace":"A","name":"Mod","version_number":"1.10.0","file_format":"zip","file_size":1,"dependencies":[]}
+{"namespace":"B","name":"Mod","version_number":"3.0.0","file_format":"zip","file_size":1,"dependencies":[]}
+{"namespace":"A","name":"Mod","version_number":"1.2.0","file_format":"zip","file_size":1,"dependencies":[]}"#;
+
+    let client = mock_client(&[("/api/experimental/package-index/", index)]).await;
+
+    let versions = client.get_package_versions("A-Mod").await?;
+    let versions: Vec<_> = versions.iter().map(VersionId::as_str).collect();
+    assert_eq!(versions, ["A-Mod-1.10.0", "A-Mod-1.2.0", "A-Mod-1.0.0"]);
+
+    assert!(matches!(
+        client.get_package_versions("C-Mod").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_url_works() -> Result<()> {
+    let client = mock_client(&[("/source.zip", "source")]).await;
+
+    let url = format!("{}/source.zip", client.base_url).parse().unwrap();
+    assert_eq!(&client.download_url(&url).await?[..], b"source");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deserialize_error_keeps_body() -> Result<()> {
+    let long = format!(r#"{{"unexpected":"{}"}}"#, "a".repeat(MAX_ERROR_BODY_LEN));
+    let client = mock_client(&[
+        ("/api/experimental/package/A/Mod/", r#"{"unexpected":true}"#),
+        ("/api/experimental/package/B/Mod/", &long),
+    ])
+    .await;
+
+    match client.get_package("A-Mod").await {
+        Err(Error::Deserialize { body, .. }) => assert_eq!(body, r#"{"unexpected":true}"#),
+        other => panic!("expected Deserialize error, got {:?}", other),
+    }
+
+    match client.get_package("B-Mod").await {
+        Err(Error::Deserialize { body, .. }) => assert_eq!(body.len(), MAX_ERROR_BODY_LEN),
+        other => panic!("expected Deserialize error, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+fn package_json(full_name: &str) -> String {
+    let id: PackageId = full_name.parse().unwrap();
+    let latest = version_json(&format!("{}-1.0.0", full_name), &[]);
+
+    format!(
+        r#"{{
+            "namespace": "{namespace}",
+            "name": "{name}",
+            "full_name": "{full_name}",
+            "owner": "{namespace}",
+            "package_url": "https://thunderstore.io/package/{namespace}/{name}/",
+            "date_created": "2024-01-01T00:00:00Z",
+            "date_updated": "2024-01-01T00:00:00Z",
+            "rating_score": 0,
+            "is_pinned": false,
+            "is_deprecated": false,
+            "total_downloads": 0,
+            "latest": {latest},
+            "community_listings": []
+        }}"#,
+        namespace = id.namespace(),
+        name = id.name(),
+    )
+}
+
+#[test]
+fn package_listing_in_works() -> Result<()> {
+    let json = package_json("A-Mod").replace(
+        r#""community_listings": []"#,
+        r#""community_listings": [
+            {
+                "has_nsfw_content": true,
+                "categories": ["Tools"],
+                "community": "valheim",
+                "review_status": "approved"
+            }
+        ]"#,
+    );
+    let package: models::Package = serde_json::from_str(&json)?;
+
+    let listing = package.listing_in("valheim").unwrap();
+    assert!(listing.has_nsfw_content);
+    assert!(listing.categories.contains("Tools"));
+    assert!(package.listing_in("lethal-company").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_packages_batch_reports_per_package() -> Result<()> {
+    let a = package_json("A-Mod");
+    let c = package_json("C-Mod");
+
+    let client = mock_client(&[
+        ("/api/experimental/package/A/Mod/", &a),
+        ("/api/experimental/package/C/Mod/", &c),
+    ])
+    .await;
+
+    let results = client
+        .get_packages_batch(["A-Mod", "B-Mod", "C-Mod"])
+        .await?;
+    assert_eq!(results[0].1.as_ref().unwrap().full_name, "A-Mod");
+    assert!(matches!(results[1].1, Err(Error::NotFound { .. })));
+    assert_eq!(results[2].1.as_ref().unwrap().full_name, "C-Mod");
+
+    let packages = client.get_packages(["C-Mod", "A-Mod"]).await?;
+    let names: Vec<_> = packages.iter().map(|p| p.full_name.as_str()).collect();
+    assert_eq!(names, ["C-Mod", "A-Mod"]);
+
+    assert!(client.get_packages(["A-Mod", "B-Mod"]).await.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn ids_from_v1_models_work() {
+    let package = package_v1(
+        "Kesomannen-GaleModManager",
+        &[("0.6.0", "2024-01-01T00:00:00Z")],
+    );
+
+    assert_eq!(
+        PackageId::from(&package),
+        PackageId::new("Kesomannen", "GaleModManager")
+    );
+    assert_eq!(
+        VersionId::from(&package.versions[0]),
+        VersionId::new("Kesomannen", "GaleModManager", "0.6.0")
+    );
+}
+
+/// Feeds `body` to a [`util::Splitter`] in chunks of `chunk_size` bytes.
+fn split_json(body: &str, boundary: util::Boundary, chunk_size: usize) -> Vec<String> {
+    let mut splitter = util::Splitter::new(boundary);
+    let mut values: Vec<_> = body
+        .as_bytes()
+        .chunks(chunk_size)
+        .flat_map(|chunk| splitter.push(chunk))
+        .collect();
+    values.extend(splitter.finish());
+
+    values
+        .into_iter()
+        .map(|value| String::from_utf8(value).unwrap())
+        .collect()
+}
+
+#[test]
+fn splitter_finds_array_elements() {
+    let body = r#" [ {"a": "}]},", "b": [1, {"c": "\"]"}]}, {"ü": []} ,
+        {"last": true}]"#;
+
+    for chunk_size in [1, 2, 3, 7, body.len()] {
+        assert_eq!(
+            split_json(body, util::Boundary::ArrayElements, chunk_size),
+            [
+                r#"{"a": "}]},", "b": [1, {"c": "\"]"}]}"#,
+                r#"{"ü": []}"#,
+                r#"{"last": true}"#,
+            ]
+        );
+    }
+
+    assert!(split_json("[]", util::Boundary::ArrayElements, 1).is_empty());
+}
+
+#[test]
+fn splitter_finds_lines() {
+    let body = "{\"a\":1}\r\n\n{\"b\":2}\n{\"c\":3}";
+
+    for chunk_size in [1, 4, body.len()] {
+        assert_eq!(
+            split_json(body, util::Boundary::Lines, chunk_size),
+            [r#"{"a":1}"#, r#"{"b":2}"#, r#"{"c":3}"#]
+        );
+    }
+}
+
+#[tokio::test]
+async fn stream_package_index_parses_lines() -> Result<()> {
+    let client = mock_client(&[("/api/experimental/package-index/", PACKAGE_INDEX)]).await;
+
+    let entries: Vec<_> = client.stream_package_index().await?.try_collect().await?;
+    let namespaces: Vec<_> = entries.iter().map(|e| e.namespace.as_str()).collect();
+    assert_eq!(namespaces, ["A", "B"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_all_packages_by_namespace_deduplicates() -> Result<()> {
+    let index = format!(
+        "{}\n{}",
+        PACKAGE_INDEX,
+        r#"{"namespace":"A","name":"Mod","version_number":"1.1.0","file_format":"zip","file_size":1024,"dependencies":[]}"#
+    );
+
+    let client = mock_client(&[
+        ("/api/experimental/package-index/", &index),
+        ("/api/experimental/package/A/Mod/", &package_json("A-Mod")),
+    ])
+    .await;
+
+    let packages = client.list_all_packages_by_namespace("a").await?;
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].full_name, "A-Mod");
+
+    assert!(matches!(
+        client.list_all_packages_by_namespace("C").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_all_packages_by_namespace_uses_index_cache() -> Result<()> {
+    let mut client = mock_client(&[("/api/experimental/package-index/", PACKAGE_INDEX)]).await;
+    client.index_cache = Some(Arc::new(IndexCache::new(Duration::from_secs(60))));
+    client.get_package_index_cached().await?;
+
+    // drop the index route, so only the cached copy can be used
+    let cached = client.index_cache.clone();
+    let mut client =
+        mock_client(&[("/api/experimental/package/A/Mod/", &package_json("A-Mod"))]).await;
+    client.index_cache = cached;
+
+    let packages = client.list_all_packages_by_namespace("A").await?;
+    assert_eq!(packages[0].full_name, "A-Mod");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream_package_index_resilient_resumes() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+
+    tokio::spawn(async move {
+        for attempt in 0.. {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let _ = socket.read(&mut [0; 1024]).await;
+
+            // the first response is cut off in the middle of the second line
+            let body = match attempt {
+                0 => &PACKAGE_INDEX[..PACKAGE_INDEX.len() - 10],
+                _ => PACKAGE_INDEX,
+            };
+
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                PACKAGE_INDEX.len()
+            );
+            socket.write_all(head.as_bytes()).await.ok();
+            socket.write_all(body.as_bytes()).await.ok();
+        }
+    });
+
+    let client = Client::builder().with_base_url(base_url).build()?;
+
+    let entries: Vec<_> = client
+        .stream_package_index_resilient()
+        .try_collect()
+        .await?;
+    let namespaces: Vec<_> = entries.iter().map(|e| e.namespace.as_str()).collect();
+    assert_eq!(namespaces, ["A", "B"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream_packages_v1_filtered_skips_packages() -> Result<()> {
+    let mut packages: Vec<serde_json::Value> =
+        ["A-Modpack", "B-Deprecated", "C-Mod", "D-Nsfw", "E-Last"]
+            .iter()
+            .map(|name| {
+                serde_json::from_str(&package_v1_json(name, &[("1.0.0", "2024-01-01T00:00:00Z")]))
+                    .unwrap()
+            })
+            .collect();
+
+    packages[0]["categories"] = serde_json::json!(["Modpacks", "Mods"]);
+    packages[1]["categories"] = serde_json::json!(["Modpacks"]);
+    packages[1]["is_deprecated"] = true.into();
+    packages[3]["categories"] = serde_json::json!(["Modpacks"]);
+    packages[3]["has_nsfw_content"] = true.into();
+
+    let listing = serde_json::to_string_pretty(&packages).unwrap();
+    let client = mock_client(&[("/c/valheim/api/v1/package/", &listing)]).await;
+
+    let filter = models::PackageFilter::new()
+        .skip_deprecated()
+        .skip_nsfw()
+        .with_category("Modpacks");
+
+    let packages: Vec<_> = client
+        .stream_packages_v1_filtered("valheim", filter)
+        .await?
+        .try_collect()
+        .await?;
+    let names: Vec<_> = packages
+        .iter()
+        .map(|package| package.full_name.as_str())
+        .collect();
+
+    assert_eq!(names, ["A-Modpack"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rate_package_requires_token() -> Result<()> {
+    let package = package_v1("A-Mod", &[("1.0.0", "2024-01-01T00:00:00Z")]);
+    let mut client = mock_client(&[(
+        "/api/v1/package/10000000-0000-0000-0000-000000000000/rate/",
+        r#"{"state":"rated","score":6}"#,
+    )])
+    .await;
+
+    assert!(matches!(
+        client.rate_package(&package, true).await,
+        Err(Error::ApiTokenRequired)
+    ));
+
+    client.token = Some("tss_XXX".to_owned());
+    assert_eq!(client.rate_package(&package, true).await?, 6);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn team_endpoints_work() -> Result<()> {
+    let mut client = mock_client(&[
+        (
+            "/api/cyberstorm/team/Kesomannen/",
+            r#"{"identifier":1,"name":"Kesomannen","donation_link":null}"#,
+        ),
+        (
+            "/api/cyberstorm/team/Kesomannen/member/",
+            r#"[{"identifier":2,"username":"bobbo","avatar":null,"role":"owner"}]"#,
+        ),
+    ])
+    .await;
+
+    assert_eq!(client.get_team("Kesomannen").await?.name, "Kesomannen");
+
+    assert!(matches!(
+        client.list_team_members("Kesomannen").await,
+        Err(Error::ApiTokenRequired)
+    ));
+
+    client.token = Some("tss_XXX".to_owned());
+    let members = client.list_team_members("Kesomannen").await?;
+    assert_eq!(members[0].username, "bobbo");
+    assert_eq!(members[0].role, models::TeamMemberRole::Owner);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_package_count_works() -> Result<()> {
+    let client = mock_client(&[(
+        "/api/cyberstorm/community/valheim/",
+        r#"{"identifier":"valheim","name":"Valheim","total_download_count":100000,"total_package_count":12345}"#,
+    )])
+    .await;
+
+    assert_eq!(client.get_package_count("valheim").await?, 12345);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn publish_with_handle_allows_cancelling() -> Result<()> {
+    let uuid = uuid::Uuid::nil();
+    let user_media = r#"{
+        "uuid": "00000000-0000-0000-0000-000000000000",
+        "filename": "CoolMod.zip",
+        "size": 3,
+        "datetime_created": "2024-01-01T00:00:00Z",
+        "expiry": "2024-01-02T00:00:00Z",
+        "status": "upload_aborted"
+    }"#;
+    let initiated = format!(r#"{{"user_media":{},"upload_urls":[]}}"#, user_media);
+    let abort_route = format!("/api/experimental/usermedia/{}/abort-upload/", uuid);
+
+    // finish-upload isn't mocked, so the publish fails after the upload is initiated
+    let mut client = mock_client(&[
+        ("/api/experimental/usermedia/initiate-upload/", &initiated),
+        (&abort_route, user_media),
+    ])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let mut handle = None;
+    let metadata = usermedia::PackageMetadata::new("Kesomannen", ["valheim"]);
+
+    let result = client
+        .publish_with_handle("CoolMod", b"zip".to_vec(), metadata, |h| handle = Some(h))
+        .await;
+    assert!(matches!(result, Err(Error::NotFound { .. })));
+
+    let handle = handle.expect("handle was not reported");
+    assert_eq!(handle.uuid(), uuid);
+    assert_eq!(
+        handle.cancel().await?.status,
+        models::UserMediaStatus::UploadAborted
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dropped_publish_stops_uploading_parts() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Responds to part uploads after a delay, counting how many were started and finished.
+    struct SlowStorage {
+        started: Arc<AtomicUsize>,
+        finished: Arc<AtomicUsize>,
+    }
+
+    impl transport::Transport for SlowStorage {
+        fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            let user_media = r#"{
+                "uuid": "00000000-0000-0000-0000-000000000000",
+                "filename": "CoolMod.zip",
+                "size": 20,
+                "datetime_created": "2024-01-01T00:00:00Z",
+                "expiry": "2024-01-02T00:00:00Z",
+                "status": "upload_aborted"
+            }"#;
+
+            if request.method() != reqwest::Method::PUT {
+                let body = match request.url().path() {
+                    "/api/experimental/usermedia/initiate-upload/" => {
+                        let parts: Vec<_> = (1..=2)
+                            .map(|part_number| models::UploadPartUrl {
+                                url: format!("https://storage.invalid/part/{}", part_number),
+                                ..upload_part(part_number)
+                            })
+                            .collect();
+                        serde_json::json!({
+                            "user_media": serde_json::from_str::<serde_json::Value>(user_media).unwrap(),
+                            "upload_urls": parts,
+                        })
+                        .to_string()
+                    }
+                    _ => user_media.to_owned(),
+                };
+                return Box::pin(async move { Ok(http::Response::new(body).into()) });
+            }
+
+            self.started.fetch_add(1, Ordering::SeqCst);
+            let finished = self.finished.clone();
+
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+
+                let response = http::Response::builder()
+                    .header("ETag", "\"part\"")
+                    .body(String::new())
+                    .unwrap();
+                Ok(response.into())
+            })
+        }
+    }
+
+    let started = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(AtomicUsize::new(0));
+    let client = Client::builder()
+        .with_token("tss_XXX")
+        .with_transport(SlowStorage {
+            started: started.clone(),
+            finished: finished.clone(),
+        })
+        .build()?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let publisher = client.clone();
+    let task = tokio::spawn(async move {
+        let metadata = usermedia::PackageMetadata::new("Kesomannen", ["valheim"]);
+        publisher
+            .publish_with_handle("CoolMod", vec![0; 20], metadata, |handle| {
+                tx.send(handle).ok();
+            })
+            .await
+    });
+
+    let handle = rx.await.expect("upload was never initiated");
+    while started.load(Ordering::SeqCst) < 2 {
+        tokio::task::yield_now().await;
+    }
+
+    task.abort();
+    assert!(task.await.unwrap_err().is_cancelled());
+
+    // give the parts time to finish, if they were still running
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(finished.load(Ordering::SeqCst), 0);
+
+    assert_eq!(
+        handle.cancel().await?.status,
+        models::UserMediaStatus::UploadAborted
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upload_session_tracks_parts() -> Result<()> {
+    let uuid = uuid::Uuid::nil();
+    let user_media = r#"{
+        "uuid": "00000000-0000-0000-0000-000000000000",
+        "filename": "CoolMod.zip",
+        "size": 20,
+        "datetime_created": "2024-01-01T00:00:00Z",
+        "expiry": "2024-01-02T00:00:00Z",
+        "status": "upload_complete"
+    }"#;
+    let initiated = format!(r#"{{"user_media":{},"upload_urls":[]}}"#, user_media);
+    let finish_route = format!("/api/experimental/usermedia/{}/finish-upload/", uuid);
+
+    let mut client = mock_server(&[
+        ("/api/experimental/usermedia/initiate-upload/", &initiated),
+        (&finish_route, user_media),
+        ("/part/1", ""),
+        ("/part/2", ""),
+    ])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let session = client.start_upload("CoolMod", 0).await?;
+    assert_eq!(session.uuid(), uuid);
+    assert!(session.is_complete());
+
+    let upload_urls: Vec<_> = (1..=2)
+        .map(|part_number| models::UploadPartUrl {
+            url: format!("{}/part/{}", client.base_url, part_number),
+            ..upload_part(part_number)
+        })
+        .collect();
+
+    let mut session = client.resume_upload_session(uuid, upload_urls, vec![completed_part(1)])?;
+    let remaining: Vec<_> = session.remaining_parts().map(|p| p.part_number).collect();
+    assert_eq!(remaining, [2]);
+
+    assert!(matches!(
+        session.upload_part(2, vec![0; 5]).await,
+        Err(Error::InvalidUploadPart { part_number: 2 })
+    ));
+    assert!(matches!(
+        session.clone().finish().await,
+        Err(Error::InvalidUploadParts)
+    ));
+
+    session.upload_part(2, vec![0; 10]).await?;
+    assert!(session.is_complete());
+    assert_eq!(session.completed_parts()[1].tag, "\"mock\"");
+
+    assert_eq!(
+        session.finish().await?.status,
+        models::UserMediaStatus::UploadComplete
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upload_parts_go_through_transport() -> Result<()> {
+    // the storage host doesn't resolve, so the parts can only be uploaded through the transport
+    struct Storage(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl transport::Transport for Storage {
+        fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            let path = request.url().path().to_owned();
+
+            let response = if request.method() == reqwest::Method::PUT {
+                self.0.lock().unwrap().push(path.clone());
+                http::Response::builder()
+                    .header("ETag", format!("\"{}\"", path))
+                    .body(String::new())
+            } else {
+                http::Response::builder().body(
+                    r#"{
+                        "uuid": "00000000-0000-0000-0000-000000000000",
+                        "filename": "CoolMod.zip",
+                        "size": 20,
+                        "datetime_created": "2024-01-01T00:00:00Z",
+                        "expiry": "2024-01-02T00:00:00Z",
+                        "status": "upload_complete"
+                    }"#
+                    .to_owned(),
+                )
+            };
+
+            Box::pin(async move { Ok(response.unwrap().into()) })
+        }
+    }
+
+    let uploaded = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let client = Client::builder()
+        .with_token("tss_XXX")
+        .with_transport(Storage(uploaded.clone()))
+        .build()?;
+
+    let upload_urls: Vec<_> = (1..=2)
+        .map(|part_number| models::UploadPartUrl {
+            url: format!("https://storage.invalid/part/{}", part_number),
+            ..upload_part(part_number)
+        })
+        .collect();
+
+    client
+        .resume_upload(
+            uuid::Uuid::nil(),
+            upload_urls.clone(),
+            Vec::new(),
+            vec![0; 20],
+        )
+        .await?;
+
+    let mut paths = uploaded.lock().unwrap().clone();
+    paths.sort();
+    assert_eq!(paths, ["/part/1", "/part/2"]);
+
+    let mut session = client.resume_upload_session(uuid::Uuid::nil(), upload_urls, Vec::new())?;
+    session.upload_part(1, vec![0; 10]).await?;
+    assert_eq!(session.completed_parts()[0].tag, "\"/part/1\"");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upload_part_without_etag_fails() -> Result<()> {
+    struct NoETag;
+
+    impl transport::Transport for NoETag {
+        fn execute(
+            &self,
+            _: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            Box::pin(async move { Ok(http::Response::new(String::new()).into()) })
+        }
+    }
+
+    let client = Client::builder().with_transport(NoETag).build()?;
+    let mut session =
+        client.resume_upload_session(uuid::Uuid::nil(), vec![upload_part(1)], Vec::new())?;
+
+    assert!(matches!(
+        session.upload_part(1, vec![0; 10]).await,
+        Err(Error::MissingETag { part_number: 1 })
+    ));
+    assert!(!session.is_complete());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prefetch_readmes_reports_per_version() -> Result<()> {
+    let client = mock_client(&[
+        (
+            "/api/experimental/package/A/Mod/1.0.0/readme/",
+            r##"{"markdown":"# A"}"##,
+        ),
+        (
+            "/api/experimental/package/C/Mod/1.0.0/readme/",
+            r##"{"markdown":"# C"}"##,
+        ),
+        (
+            "/api/experimental/package/C/Mod/1.0.0/changelog/",
+            r#"{"markdown":"- Released"}"#,
+        ),
+    ])
+    .await;
+
+    let results = client
+        .prefetch_readmes(["A-Mod-1.0.0", "B-Mod-1.0.0", "C-Mod-1.0.0"])
+        .await?;
+    let ids: Vec<_> = results.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, ["A-Mod-1.0.0", "B-Mod-1.0.0", "C-Mod-1.0.0"]);
+    assert_eq!(results[0].1.as_ref().unwrap(), "# A");
+    assert!(matches!(results[1].1, Err(Error::NotFound { .. })));
+    assert_eq!(results[2].1.as_ref().unwrap(), "# C");
+
+    let results = client
+        .prefetch_changelogs(["A-Mod-1.0.0", "C-Mod-1.0.0"])
+        .await?;
+    assert!(matches!(results[0].1, Err(Error::NotFound { .. })));
+    assert_eq!(results[1].1.as_ref().unwrap(), "- Released");
+
+    assert!(client.prefetch_readmes(["invalid"]).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_readme_html_renders_markdown() -> Result<()> {
+    let client = mock_client(&[
+        (
+            "/api/experimental/package/A/Mod/1.0.0/readme/",
+            r##"{"markdown":"# Mod"}"##,
+        ),
+        (
+            "/api/experimental/frontend/render-markdown/",
+            r#"{"html":"<h1>Mod</h1>"}"#,
+        ),
+    ])
+    .await;
+
+    assert_eq!(client.get_readme_html("A-Mod-1.0.0").await?, "<h1>Mod</h1>");
+    assert!(matches!(
+        client.get_changelog_html("A-Mod-1.0.0").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_changelog_sections_splits_versions() -> Result<()> {
+    let markdown = "# Changelog\n\nAll notable changes.\n\n## [1.1] - 2024-01-01\n\n### Fixed\n- Crash\n\n```\n## 0.9.0 in a code block\n```\n\n# v1.0.0\n- Released";
+    let body = serde_json::json!({ "markdown": markdown }).to_string();
+
+    let client = mock_client(&[("/api/experimental/package/A/Mod/1.1.0/changelog/", &body)]).await;
+
+    let entries = client.get_changelog_sections("A-Mod-1.1.0").await?;
+    let versions: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.version.as_ref().map(ToString::to_string))
+        .collect();
+
+    assert_eq!(
+        versions,
+        [None, Some("1.1.0".to_owned()), Some("1.0.0".to_owned())]
+    );
+    assert_eq!(entries[0].body, "# Changelog\n\nAll notable changes.");
+    assert_eq!(
+        entries[1].body,
+        "### Fixed\n- Crash\n\n```\n## 0.9.0 in a code block\n```"
+    );
+    assert_eq!(entries[2].body, "- Released");
+
+    assert!(models::ChangelogEntry::parse("## 1.0.0")[0].body.is_empty());
+    assert!(models::ChangelogEntry::parse("").is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_base_url_is_used_for_archives() -> Result<()> {
+    let cdn = mock_server(&[("/package/download/A/Mod/1.0.0/", "zip")]).await;
+    let mut client =
+        mock_server(&[("/api/experimental/package/A/Mod/", &package_json("A-Mod"))]).await;
+
+    assert!(matches!(
+        client.download("A-Mod-1.0.0").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    client.download_base_url = Some(cdn.base_url.clone());
+    assert_eq!(client.download("A-Mod-1.0.0").await?, "zip");
+    client.get_package("A-Mod").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn base_path_is_inserted_into_urls() -> Result<()> {
+    let uuid = uuid::Uuid::nil();
+    let usermedia_route = format!("/thunderstore/api/experimental/usermedia/{}/", uuid);
+    let user_media = r#"{
+        "uuid": "00000000-0000-0000-0000-000000000000",
+        "filename": "CoolMod.zip",
+        "size": 3,
+        "datetime_created": "2024-01-01T00:00:00Z",
+        "expiry": "2024-01-02T00:00:00Z",
+        "status": "upload_complete"
+    }"#;
+
+    let transport = MockTransport::new()
+        .with_response(
+            "/thunderstore/api/experimental/community/valheim/",
+            community_json("valheim"),
+        )
+        .with_response(
+            "/thunderstore/c/valheim/api/v1/package-metrics/A/Mod/",
+            r#"{"downloads":10,"rating_score":1,"latest_version":"1.0.0"}"#,
+        )
+        .with_response(usermedia_route, user_media)
+        .with_response("/thunderstore/package/download/A/Mod/1.0.0/", "zip");
+
+    let client = Client::builder()
+        .with_transport(transport)
+        .with_base_url("https://thunderstore.io/")
+        .with_base_path("/thunderstore/")
+        .with_token("tss_XXX")
+        .build()?;
+
+    client.get_community("valheim").await?;
+    client.get_metrics("valheim", "A-Mod").await?;
+    client.get_usermedia(uuid).await?;
+    assert_eq!(client.download("A-Mod-1.0.0").await?, "zip");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn custom_transport_receives_requests() -> Result<()> {
+    struct Echo;
+
+    impl transport::Transport for Echo {
+        fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            let body = serde_json::json!({ "markdown": request.url().path() }).to_string();
+            Box::pin(async move { Ok(http::Response::new(body).into()) })
+        }
+    }
+
+    let client = Client::builder().with_transport(Echo).build()?;
+
+    assert_eq!(
+        client.get_readme("A-Mod-1.0.0").await?,
+        "/api/experimental/package/A/Mod/1.0.0/readme/"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn exists_falls_back_to_get() -> Result<()> {
+    struct NoHead;
+
+    impl transport::Transport for NoHead {
+        fn execute(
+            &self,
+            request: reqwest::Request,
+        ) -> futures_util::future::BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+            let status = match (request.method(), request.url().path()) {
+                (&reqwest::Method::HEAD, _) => 405,
+                (_, "/api/experimental/package/A/Mod/1.0.0/") => 200,
+                _ => 404,
+            };
+
+            let response = http::Response::builder()
+                .status(status)
+                .body(String::new())
+                .unwrap();
+            Box::pin(async move { Ok(response.into()) })
+        }
+    }
+
+    let client = Client::builder().with_transport(NoHead).build()?;
+
+    assert!(client.version_exists("A-Mod-1.0.0").await?);
+    assert!(!client.version_exists("A-Mod-2.0.0").await?);
+
+    Ok(())
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn mock_client_serves_fixtures() -> Result<()> {
+    use transport::fixtures;
+
+    let client = Client::with_mock([
+        (
+            "/api/experimental/package/Kesomannen/GaleModManager/",
+            fixtures::PACKAGE,
+        ),
+        (
+            "/api/experimental/package/Kesomannen/GaleModManager/0.6.0/",
+            fixtures::VERSION,
+        ),
+        (
+            "/api/experimental/community/lethal-company/",
+            fixtures::COMMUNITY,
+        ),
+        (
+            "/c/lethal-company/api/v1/package-metrics/Kesomannen/GaleModManager/",
+            fixtures::METRICS,
+        ),
+        ("/api/experimental/package-index/", fixtures::PACKAGE_INDEX),
+        ("/c/lethal-company/api/v1/package/", fixtures::PACKAGES_V1),
+    ]);
+
+    let package = client.get_package("Kesomannen-GaleModManager").await?;
+    let version = client
+        .get_version("Kesomannen-GaleModManager-0.6.0")
+        .await?;
+    assert_eq!(package.latest, version);
+
+    client.get_community("lethal-company").await?;
+    client
+        .get_metrics("lethal-company", "Kesomannen-GaleModManager")
+        .await?;
+    assert_eq!(client.get_package_index().await?.len(), 3);
+    assert_eq!(client.list_packages_v1("lethal-company").await?.len(), 1);
+
+    assert!(matches!(
+        client.get_community("valheim").await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn connection_failure_is_connect_error() -> Result<()> {
+    // bind to get a free port, then close it so the connection is refused
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+    drop(listener);
+
+    let client = Client::builder().with_base_url(base_url).build()?;
+
+    assert!(matches!(
+        client.get_community("valheim").await,
+        Err(Error::Connect(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn handshake_failure_is_tls_error() -> Result<()> {
+    // answer the client hello with plain HTTP, which fails the handshake
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("https://{}", listener.local_addr()?);
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0; 1024];
+        let _ = socket.read(&mut buf).await;
+        socket
+            .write_all(b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n")
+            .await
+            .ok();
+    });
+
+    let client = Client::builder().with_base_url(base_url).build()?;
+    let err = client.get_community("valheim").await.unwrap_err();
+
+    assert!(matches!(err, Error::Tls(_)), "{:?}", err);
+    assert!(!err.is_transient());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_response_size_rejects_large_responses() -> Result<()> {
+    let mut client = mock_client(&[(
+        "/api/experimental/community/valheim/",
+        &community_json("valheim"),
+    )])
+    .await;
+
+    client.max_response_size = Some(10);
+    assert!(matches!(
+        client.get_community("valheim").await,
+        Err(Error::ResponseTooLarge { limit: 10 })
+    ));
+
+    client.max_response_size = Some(100_000);
+    client.get_community("valheim").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_response_size_applies_while_streaming() -> Result<()> {
+    // no Content-Length, so the limit can only be checked as the body arrives
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let _ = socket.read(&mut [0; 1024]).await;
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n")
+            .await
+            .ok();
+        socket.write_all(&[0; 1000]).await.ok();
+    });
+
+    let client = Client::builder()
+        .with_base_url(base_url)
+        .with_max_response_size(100)
+        .build()?;
+
+    assert!(matches!(
+        client.download("A-Mod-1.0.0").await,
+        Err(Error::ResponseTooLarge { limit: 100 })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn submission_will_require_approval_checks_communities() -> Result<()> {
+    let mut strict: serde_json::Value = serde_json::from_str(&community_json("strict")).unwrap();
+    strict["require_package_listing_approval"] = true.into();
+    let strict = strict.to_string();
+
+    let client = mock_client(&[
+        (
+            "/api/experimental/community/valheim/",
+            &community_json("valheim"),
+        ),
+        ("/api/experimental/community/strict/", &strict),
+    ])
+    .await;
+
+    let metadata = usermedia::PackageMetadata::new("Kesomannen", ["valheim"]);
+    assert!(!client.submission_will_require_approval(&metadata).await?);
+
+    let metadata = metadata.in_community("strict");
+    assert!(client.submission_will_require_approval(&metadata).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_usermedia_works() -> Result<()> {
+    let uuid = uuid::Uuid::nil();
+    let route = format!("/api/experimental/usermedia/{}/", uuid);
+
+    let mut client = mock_client(&[(
+        &route,
+        r#"{
+            "uuid": "00000000-0000-0000-0000-000000000000",
+            "filename": "CoolMod.zip",
+            "size": 1024,
+            "datetime_created": "2024-01-01T00:00:00Z",
+            "expiry": "2024-01-02T00:00:00Z",
+            "status": "upload_error"
+        }"#,
+    )])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let media = client.get_usermedia(uuid).await?;
+    assert_eq!(media.status, models::UserMediaStatus::UploadError);
+
+    assert!(matches!(
+        client.get_usermedia(uuid::Uuid::max()).await,
+        Err(Error::NotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn submit_existing_checks_status() -> Result<()> {
+    let usermedia = |uuid: uuid::Uuid, status: &str| {
+        format!(
+            r#"{{
+                "uuid": "{uuid}",
+                "filename": "CoolMod.zip",
+                "size": 1024,
+                "datetime_created": "2024-01-01T00:00:00Z",
+                "expiry": "2024-01-02T00:00:00Z",
+                "status": "{status}"
+            }}"#
+        )
+    };
+
+    let pending = uuid::Uuid::nil();
+    let complete = uuid::Uuid::max();
+    let pending_route = format!("/api/experimental/usermedia/{}/", pending);
+    let complete_route = format!("/api/experimental/usermedia/{}/", complete);
+    let pending_body = usermedia(pending, "upload_created");
+    let complete_body = usermedia(complete, "upload_complete");
+
+    let mut client = mock_client_with_status(&[
+        (&pending_route, 200, &pending_body),
+        (&complete_route, 200, &complete_body),
+        (
+            "/api/experimental/submission/submit/",
+            400,
+            r#"{"communities":["Object with slug=notacommunity does not exist."]}"#,
+        ),
+    ])
+    .await;
+    client.token = Some("tss_XXX".to_owned());
+
+    let metadata = || usermedia::PackageMetadata::new("Kesomannen", ["notacommunity"]);
+
+    assert!(matches!(
+        client.submit_existing(pending, metadata()).await,
+        Err(Error::UploadNotComplete {
+            status: models::UserMediaStatus::UploadCreated
+        })
+    ));
+
+    // the upload is complete, so the submission reaches the server
+    assert!(matches!(
+        client.submit_existing(complete, metadata()).await,
+        Err(Error::Submission(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_packages_v1_sorted_works() -> Result<()> {
+    let mut packages: Vec<serde_json::Value> = ["b-Mod", "A-Mod", "C-Mod"]
+        .iter()
+        .map(|name| {
+            serde_json::from_str(&package_v1_json(name, &[("1.0.0", "2024-01-01T00:00:00Z")]))
+                .unwrap()
+        })
+        .collect();
+
+    packages[0]["rating_score"] = 10.into();
+    packages[1]["rating_score"] = 2.into();
+    packages[2]["rating_score"] = 7.into();
+
+    let listing = serde_json::to_string(&packages).unwrap();
+    let client = mock_client(&[("/c/valheim/api/v1/package/", &listing)]).await;
+
+    let names = |packages: Vec<models::PackageV1>| -> Vec<String> {
+        packages
+            .into_iter()
+            .map(|package| package.full_name)
+            .collect()
+    };
+
+    let by_rating = client
+        .list_packages_v1_sorted(
+            "valheim",
+            models::SortKey::Rating,
+            models::SortOrder::Descending,
+        )
+        .await?;
+    assert_eq!(names(by_rating), ["b-Mod", "C-Mod", "A-Mod"]);
+
+    let by_name = client
+        .list_packages_v1_sorted(
+            "valheim",
+            models::SortKey::Name,
+            models::SortOrder::Ascending,
+        )
+        .await?;
+    assert_eq!(names(by_name), ["A-Mod", "b-Mod", "C-Mod"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_package_cached_uses_etag() -> Result<()> {
+    let a = package_json("A-Mod");
+    let mut client = mock_server_with_status(&[
+        ("/api/experimental/package/A/Mod/", 200, &a),
+        ("/api/experimental/package/B/Mod/", 304, ""),
+    ])
+    .await;
+    client.package_cache = Some(Default::default());
+
+    let package = client.get_package_cached("A-Mod").await?;
+
+    let cache = client.package_cache.as_ref().unwrap();
+    let (etag, _) = cache.entries.lock().unwrap()[&PackageId::new("A", "Mod")].clone();
+    assert_eq!(etag, "\"mock\"");
+
+    // pretend B-Mod was fetched before, so the server responds with 304 Not Modified
+    cache
+        .entries
+        .lock()
+        .unwrap()
+        .insert(PackageId::new("B", "Mod"), (etag, package.clone()));
+
+    assert_eq!(client.get_package_cached("B-Mod").await?, package);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_package_cached_rejects_unexpected_not_modified() -> Result<()> {
+    let mut client =
+        mock_client_with_status(&[("/api/experimental/package/A/Mod/", 304, "")]).await;
+    client.package_cache = Some(Default::default());
+
+    assert!(matches!(
+        client.get_package_cached("A-Mod").await,
+        Err(Error::UnexpectedNotModified)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_latest_version_works() -> Result<()> {
+    let client = mock_client(&[(
+        "/api/experimental/package/Kesomannen/CoolMod/",
+        &package_json("Kesomannen-CoolMod"),
+    )])
+    .await;
+
+    assert_eq!(
+        client.get_latest_version("Kesomannen-CoolMod").await?,
+        VersionId::new("Kesomannen", "CoolMod", "1.0.0")
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn readme_from_zip_bytes_works() {
+    let data = zip_bytes(&[
+        ("docs/README.md", b"# Nested"),
+        ("README.md", "\u{feff}# CoolMod".as_bytes()),
+    ]);
+    assert_eq!(
+        models::readme_from_zip_bytes(&data).as_deref(),
+        Some("# CoolMod")
+    );
+
+    let data = zip_bytes(&[("docs/README.md", b"# Nested")]);
+    assert_eq!(models::readme_from_zip_bytes(&data), None);
+    assert_eq!(models::readme_from_zip_bytes(b"not a zip"), None);
+}
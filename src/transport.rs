@@ -0,0 +1,208 @@
+//! Pluggable transports for sending requests.
+//!
+//! By default, a [`Client`] sends requests with its [`reqwest::Client`]. A custom [`Transport`],
+//! set with [`ClientBuilder::with_transport`](crate::ClientBuilder::with_transport), replaces
+//! this, for example to serve canned responses in tests.
+//!
+//! With the `test-util` feature, this module also provides [`MockTransport`], which responds
+//! with bodies keyed by path, and a set of [`fixtures`] to respond with.
+
+use futures_util::future::BoxFuture;
+use std::{fmt::Debug, sync::Arc};
+
+#[cfg(any(test, feature = "test-util"))]
+use crate::Client;
+#[cfg(any(test, feature = "test-util"))]
+use reqwest::StatusCode;
+#[cfg(any(test, feature = "test-util"))]
+use std::collections::HashMap;
+
+/// Sends requests on behalf of a [`Client`](crate::Client).
+///
+/// The transport receives each request after custom headers and the request hook have been
+/// applied. Error statuses are handled by the client as usual, so a transport only has to
+/// produce a response.
+///
+/// This includes uploading the parts of a package, which are sent to the storage URLs returned
+/// by Thunderstore instead of the base URL.
+pub trait Transport: Send + Sync + 'static {
+    /// Sends a request and returns the response.
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, reqwest::Result<reqwest::Response>>;
+}
+
+#[derive(Clone)]
+pub(crate) struct SharedTransport(pub(crate) Arc<dyn Transport>);
+
+impl Debug for SharedTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
+/// A [`Transport`] which responds with canned bodies, keyed by path and query.
+///
+/// Paths which don't have a response get a `404 Not Found`. The method of the request is ignored.
+///
+/// ## Example
+///
+/// ```
+/// use thunderstore::transport::{fixtures, MockTransport};
+///
+/// # async fn run() -> thunderstore::Result<()> {
+/// let transport = MockTransport::new()
+///     .with_response("/api/experimental/package/Kesomannen/GaleModManager/", fixtures::PACKAGE)
+///     .with_status("/api/experimental/community/valheim/", 500, "");
+///
+/// let client = thunderstore::Client::builder()
+///     .with_transport(transport)
+///     .build()?;
+///
+/// let package = client.get_package("Kesomannen-GaleModManager").await?;
+/// assert_eq!(package.latest.version_number, semver::Version::new(0, 6, 0));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    routes: HashMap<String, (StatusCode, String)>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MockTransport {
+    /// Creates a transport without any responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Responds to requests for `path` with a `200 OK` and `body`.
+    ///
+    /// `path` includes the query, if any, like `/api/experimental/community/?cursor=2`.
+    pub fn with_response(self, path: impl Into<String>, body: impl Into<String>) -> Self {
+        self.with_status(path, 200, body)
+    }
+
+    /// Responds to requests for `path` with the given status code and `body`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `status` is not a valid status code.
+    pub fn with_status(
+        mut self,
+        path: impl Into<String>,
+        status: u16,
+        body: impl Into<String>,
+    ) -> Self {
+        let status = StatusCode::from_u16(status).expect("invalid status code");
+        self.routes.insert(path.into(), (status, body.into()));
+        self
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl<P, B> FromIterator<(P, B)> for MockTransport
+where
+    P: Into<String>,
+    B: Into<String>,
+{
+    fn from_iter<T: IntoIterator<Item = (P, B)>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |transport, (path, body)| {
+                transport.with_response(path, body)
+            })
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Transport for MockTransport {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+        let url = request.url();
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_owned(),
+        };
+
+        let (status, body) = self
+            .routes
+            .get(&target)
+            .cloned()
+            .unwrap_or_else(|| (StatusCode::NOT_FOUND, "{}".to_owned()));
+
+        let response = http::Response::builder()
+            .status(status)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .expect("mock response is valid");
+
+        Box::pin(async move { Ok(response.into()) })
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Client {
+    /// Creates a client which responds to requests with canned bodies instead of using the network.
+    ///
+    /// `responses` are pairs of paths and bodies, see [`MockTransport`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use thunderstore::transport::fixtures;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::with_mock([
+    ///     ("/api/experimental/community/lethal-company/", fixtures::COMMUNITY),
+    /// ]);
+    ///
+    /// let community = client.get_community("lethal-company").await?;
+    /// assert_eq!(community.name, "Lethal Company");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_mock<P, B>(responses: impl IntoIterator<Item = (P, B)>) -> Self
+    where
+        P: Into<String>,
+        B: Into<String>,
+    {
+        Self {
+            transport: Some(SharedTransport(Arc::new(
+                responses.into_iter().collect::<MockTransport>(),
+            ))),
+            ..Self::default()
+        }
+    }
+}
+
+/// Sample response bodies for use with [`MockTransport`].
+///
+/// The packages are versions `0.6.0` and `0.5.0` of `Kesomannen-GaleModManager`,
+/// listed in the `lethal-company` community.
+#[cfg(any(test, feature = "test-util"))]
+pub mod fixtures {
+    /// A [`Package`](crate::models::Package), from `/api/experimental/package/{namespace}/{name}/`.
+    pub const PACKAGE: &str = include_str!("fixtures/package.json");
+
+    /// A [`PackageVersion`](crate::models::PackageVersion), from
+    /// `/api/experimental/package/{namespace}/{name}/{version}/`.
+    pub const VERSION: &str = include_str!("fixtures/version.json");
+
+    /// A [`Community`](crate::models::Community), from `/api/experimental/community/{community}/`.
+    pub const COMMUNITY: &str = include_str!("fixtures/community.json");
+
+    /// A [`PackageMetrics`](crate::models::PackageMetrics), from
+    /// `/c/{community}/api/v1/package-metrics/{namespace}/{name}/`.
+    pub const METRICS: &str = include_str!("fixtures/metrics.json");
+
+    /// The package index, with one [`PackageIndexEntry`](crate::models::PackageIndexEntry) per line,
+    /// from `/api/experimental/package-index/`.
+    pub const PACKAGE_INDEX: &str = include_str!("fixtures/package_index.jsonl");
+
+    /// A list of [`PackageV1`](crate::models::PackageV1), from `/c/{community}/api/v1/package/`.
+    pub const PACKAGES_V1: &str = include_str!("fixtures/packages_v1.json");
+}
@@ -1,32 +1,36 @@
-use crate::{models::*, Client, Error, ResponseExt, Result};
+use crate::{models::*, Client, Error, ResponseJsonExt, Result, BATCH_CONCURRENCY};
 use bytes::Bytes;
-use futures_util::future::join_all;
-use reqwest::Method;
+use futures_util::{future::try_join_all, stream, StreamExt, TryStreamExt};
+use reqwest::{Method, RequestBuilder};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    path::Path,
+};
 use tokio::fs;
 use uuid::Uuid;
 
 impl Client {
-    /// Initiates a new package upload. 
-    /// 
+    /// Initiates a new package upload.
+    ///
     /// - `name` corresponds to the name of the package and may only contain alphanumeric
-    /// characters and underscores.
-    /// 
+    ///   characters and underscores.
+    ///
     /// - `size` must be the package's size in bytes.
     ///
     /// This method returns a [`UserMediaInitiateUploadResponse`] which contains a unique UUID for the upload,
     /// which is used to identify the package throughout the upload process.
-    /// 
+    ///
     /// The response also contains a list of URLs to which the file should be uploaded, using HTTP PUT.
     /// Each upload URL responds with an ETag header, which should be used to finalize the upload.
     ///
     /// Alternatively, you can use [`Client::publish`] to upload and submit a package in one go.
-    /// 
+    ///
     /// This method requires a valid API token on the client.
-    /// 
+    ///
     /// ## Example
-    /// 
+    ///
     /// ```no_run
     /// use thunderstore::{Client, models::{UploadPartUrl, CompletedPart}};
     ///
@@ -45,10 +49,10 @@ impl Client {
     ///
     ///    // The response will return an ETag header, which is needed to complete the upload
     ///    parts.push(CompletedPart { tag: todo!(), part_number });
-    /// 
+    ///
     ///    // These requests should preferably be done concurrently to decrease upload time
     /// }
-    /// 
+    ///
     /// client.finish_upload(uuid, parts).await?;
     /// ```
     pub async fn initiate_upload(
@@ -58,15 +62,103 @@ impl Client {
     ) -> Result<UserMediaInitiateUploadResponse> {
         let url = self.usermedia_url("initiate-upload");
         let response = self
-            .auth_request(Method::POST, url)?
-            .json(&UserMediaInitiateUploadParams {
-                filename: name.into(),
-                file_size_bytes: size,
-            })
-            .send()
-            .await
-            .handle()?
-            .json()
+            .send(
+                self.auth_request(Method::POST, url)?
+                    .json(&UserMediaInitiateUploadParams {
+                        filename: name.into(),
+                        file_size_bytes: size,
+                    }),
+            )
+            .await?
+            .parse_json()
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Initiates a new package upload and returns an [`UploadSession`] to upload its parts with.
+    ///
+    /// - `name` may only contain alphanumeric characters and underscores.
+    /// - `size` must be the package's size in bytes.
+    ///
+    /// This is an alternative to calling [`Client::initiate_upload`] and [`Client::finish_upload`]
+    /// directly, where the session keeps track of which parts have been uploaded.
+    ///
+    /// This method requires a valid API token on the client.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::builder().with_token("tss_XXX").build()?;
+    ///
+    /// let data = std::fs::read("CoolMod.zip")?;
+    /// let mut session = client.start_upload("CoolMod", data.len() as u64).await?;
+    ///
+    /// let parts: Vec<_> = session.remaining_parts().cloned().collect();
+    /// for part in parts {
+    ///     let start = part.offset as usize;
+    ///     let end = start + part.length as usize;
+    ///     session.upload_part(part.part_number, data[start..end].to_vec()).await?;
+    ///
+    ///     println!("{}/{} parts uploaded", session.completed_parts().len(), session.parts().len());
+    /// }
+    ///
+    /// let user_media = session.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_upload(&self, name: impl Into<String>, size: u64) -> Result<UploadSession> {
+        let response = self.initiate_upload(name, size).await?;
+        let uuid = response.user_media.uuid.ok_or(Error::NoUploadUuidGiven)?;
+
+        self.resume_upload_session(uuid, response.upload_urls, Vec::new())
+    }
+
+    /// Creates an [`UploadSession`] for an upload which was started earlier, for example
+    /// before the program was restarted.
+    ///
+    /// - `uuid` and `upload_urls` are the ones returned when the upload was initiated.
+    /// - `already_completed` contains the parts that were uploaded before, which
+    ///   can be saved from [`UploadSession::completed_parts`].
+    ///
+    /// Returns [`Error::InvalidUploadParts`] under the same conditions as [`Client::resume_upload`].
+    /// No requests are made until a part is uploaded.
+    pub fn resume_upload_session(
+        &self,
+        uuid: Uuid,
+        upload_urls: Vec<UploadPartUrl>,
+        already_completed: Vec<CompletedPart>,
+    ) -> Result<UploadSession> {
+        // validates that the lists belong together
+        remaining_parts(upload_urls.clone(), &already_completed)?;
+
+        Ok(UploadSession {
+            client: self.clone(),
+            uuid,
+            parts: upload_urls,
+            completed: already_completed
+                .into_iter()
+                .map(|part| (part.part_number, part))
+                .collect(),
+        })
+    }
+
+    /// Fetches the current state of an upload.
+    ///
+    /// This can be used to poll [`UserMedia::status`] and check the [`UserMedia::expiry`]
+    /// of a long upload before finishing it.
+    ///
+    /// Returns [`Error::NotFound`] if there is no upload with the given UUID.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn get_usermedia(&self, uuid: Uuid) -> Result<UserMedia> {
+        let url = self.usermedia_url(uuid);
+
+        let response = self
+            .send(self.auth_request(Method::GET, url)?)
+            .await?
+            .parse_json()
             .await?;
 
         Ok(response)
@@ -79,11 +171,9 @@ impl Client {
         let url = self.usermedia_url(format_args!("{}/abort-upload", uuid));
 
         let response = self
-            .auth_request(Method::POST, url)?
-            .send()
-            .await
-            .handle()?
-            .json()
+            .send(self.auth_request(Method::POST, url)?)
+            .await?
+            .parse_json()
             .await?;
 
         Ok(response)
@@ -100,19 +190,19 @@ impl Client {
         let url = self.usermedia_url(format_args!("{}/finish-upload", uuid));
 
         let response = self
-            .auth_request(Method::POST, url)?
-            .json(&UserMediaFinishUploadParams { parts })
-            .send()
-            .await
-            .handle()?
-            .json()
+            .send(
+                self.auth_request(Method::POST, url)?
+                    .json(&UserMediaFinishUploadParams { parts }),
+            )
+            .await?
+            .parse_json()
             .await?;
 
         Ok(response)
     }
 
     /// Uploads and submits a package.
-    /// 
+    ///
     /// - `name` may only contain alphanumeric characters and underscores.
     ///
     /// This method requires a valid API token on the client.
@@ -121,27 +211,164 @@ impl Client {
         name: impl Into<String>,
         data: Vec<u8>,
         metadata: PackageMetadata,
+    ) -> Result<PackageSubmissionResult> {
+        self.publish_with_handle(name, data, metadata, |_| ()).await
+    }
+
+    /// Uploads and submits a package, handing out an [`UploadHandle`] as soon as the upload is initiated.
+    ///
+    /// - `name` may only contain alphanumeric characters and underscores.
+    /// - `on_initiated` is called with the handle before any data is uploaded.
+    ///
+    /// To cancel the publish, drop the returned future (or abort the task running it)
+    /// and call [`UploadHandle::cancel`] to abort the upload on the server. Dropping the
+    /// future stops the parts which are still uploading. At most [`BATCH_CONCURRENCY`]
+    /// parts are uploaded at once.
+    ///
+    /// This method requires a valid API token on the client.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use thunderstore::usermedia::PackageMetadata;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::builder().with_token("tss_XXX").build()?;
+    /// let (tx, rx) = tokio::sync::oneshot::channel();
+    ///
+    /// let task = tokio::spawn(async move {
+    ///     let data = std::fs::read("CoolMod.zip")?;
+    ///     let metadata = PackageMetadata::new("Kesomannen", ["lethal-company"]);
+    ///
+    ///     client
+    ///         .publish_with_handle("CoolMod", data, metadata, |handle| {
+    ///             tx.send(handle).ok();
+    ///         })
+    ///         .await
+    /// });
+    ///
+    /// // later, when the user cancels
+    /// let handle = rx.await.expect("upload was never initiated");
+    /// task.abort();
+    /// handle.cancel().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn publish_with_handle(
+        &self,
+        name: impl Into<String>,
+        data: Vec<u8>,
+        metadata: PackageMetadata,
+        on_initiated: impl FnOnce(UploadHandle),
     ) -> Result<PackageSubmissionResult> {
         let bytes = Bytes::from(data);
         let response = self.initiate_upload(name, bytes.len() as u64).await?;
 
         let uuid = response.user_media.uuid.ok_or(Error::NoUploadUuidGiven)?;
 
-        let chunks = response
-            .upload_urls
-            .into_iter()
-            .map(|part| tokio::spawn(upload_chunk(self.client.clone(), part, bytes.clone())));
+        on_initiated(UploadHandle {
+            client: self.clone(),
+            uuid,
+        });
 
-        let parts = join_all(chunks)
-            .await
-            .into_iter()
-            .flatten()
-            .collect::<Result<Vec<_>>>()?;
+        let parts = self.upload_parts(response.upload_urls, bytes).await?;
 
         self.finish_upload(uuid, parts).await?;
         self.submit_package(uuid, metadata).await
     }
 
+    /// Checks whether a submission with the given metadata will be held for review
+    /// before it's listed, because one of its communities requires listing approval.
+    ///
+    /// This fetches each community in [`PackageMetadata`] with [`Client::get_community`]
+    /// and returns `true` if any of them has [`Community::require_package_listing_approval`] set.
+    pub async fn submission_will_require_approval(
+        &self,
+        metadata: &PackageMetadata,
+    ) -> Result<bool> {
+        let communities = try_join_all(
+            metadata
+                .communities
+                .iter()
+                .map(|slug| self.get_community(slug)),
+        )
+        .await?;
+
+        Ok(communities
+            .iter()
+            .any(|community| community.require_package_listing_approval))
+    }
+
+    /// Resumes an interrupted upload and finalizes it.
+    ///
+    /// - `uuid` and `upload_urls` are the ones returned by the original call to [`Client::initiate_upload`].
+    /// - `already_completed` contains the parts that were successfully uploaded before the interruption.
+    /// - `data` must be the full contents of the package, not just the remaining parts.
+    ///
+    /// Only the parts whose `part_number` isn't present in `already_completed` are uploaded,
+    /// after which [`Client::finish_upload`] is called with the full list of parts.
+    /// Note that this will not submit the package, use [`Client::submit_package`] for that.
+    ///
+    /// Returns [`Error::InvalidUploadParts`] if `already_completed` contains a part which isn't in
+    /// `upload_urls`, or if either list contains the same part number more than once.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn resume_upload(
+        &self,
+        uuid: Uuid,
+        upload_urls: Vec<UploadPartUrl>,
+        already_completed: Vec<CompletedPart>,
+        data: Vec<u8>,
+    ) -> Result<UserMedia> {
+        let remaining = remaining_parts(upload_urls, &already_completed)?;
+
+        let mut parts = self.upload_parts(remaining, Bytes::from(data)).await?;
+        parts.extend(already_completed);
+        parts.sort_by_key(|part| part.part_number);
+
+        self.finish_upload(uuid, parts).await
+    }
+
+    async fn upload_parts(
+        &self,
+        parts: impl IntoIterator<Item = UploadPartUrl>,
+        bytes: Bytes,
+    ) -> Result<Vec<CompletedPart>> {
+        // check every part before uploading any, so a bad response doesn't leave a partial upload
+        let parts = parts
+            .into_iter()
+            .map(|part| Ok((part_slice(&bytes, &part)?, part)))
+            .collect::<Result<Vec<_>>>()?;
+
+        // the parts aren't spawned, so dropping the future cancels the uploads in flight
+        stream::iter(parts)
+            .map(|(slice, part)| {
+                let request = self.client.put(&part.url).body(slice);
+                self.upload_chunk(request, part.part_number)
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Uploads a single part and returns its ETag.
+    async fn upload_chunk(
+        &self,
+        request: RequestBuilder,
+        part_number: u32,
+    ) -> Result<CompletedPart> {
+        let response = self.send(request).await?;
+
+        let tag = response
+            .headers()
+            .get("ETag")
+            .and_then(|tag| tag.to_str().ok())
+            .ok_or(Error::MissingETag { part_number })?
+            .to_owned();
+
+        Ok(CompletedPart { tag, part_number })
+    }
+
     /// Uploads and submits a package.
     /// The name of the package is derived from the file name.
     ///
@@ -166,27 +393,183 @@ impl Client {
     }
 }
 
-async fn upload_chunk(
-    client: reqwest::Client,
-    part: UploadPartUrl,
-    bytes: Bytes,
-) -> Result<CompletedPart> {
-    let slice = bytes.slice(part.offset as usize..(part.offset + part.length) as usize);
-
-    let response = client.put(&part.url).body(slice).send().await?.error_for_status()?;
-
-    let tag = response
-        .headers()
-        .get("ETag")
-        .expect("no ETag in response")
-        .to_str()
-        .expect("ETag is not valid ascii")
-        .to_owned();
-
-    Ok(CompletedPart {
-        tag,
+/// A handle to an upload in progress, see [`Client::publish_with_handle`].
+///
+/// The handle holds a clone of the client, so it can be sent to another task.
+#[derive(Clone)]
+pub struct UploadHandle {
+    client: Client,
+    uuid: Uuid,
+}
+
+impl UploadHandle {
+    /// The UUID of the upload, which can be passed to [`Client::get_usermedia`] or [`Client::abort_upload`].
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Aborts the upload on the server. This is a shorthand for [`Client::abort_upload`].
+    ///
+    /// Note that this doesn't stop any requests that are still in flight, so the
+    /// future publishing the package should be dropped first, which stops its part uploads.
+    pub async fn cancel(&self) -> Result<UserMedia> {
+        self.client.abort_upload(self.uuid).await
+    }
+}
+
+impl std::fmt::Debug for UploadHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadHandle")
+            .field("uuid", &self.uuid)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An upload in progress, created with [`Client::start_upload`].
+///
+/// The session keeps track of which parts have been uploaded, so they don't have to be
+/// passed to [`Client::finish_upload`] by hand. Parts can be uploaded in any order.
+///
+/// The session holds a clone of the client, so it can be sent to another task.
+#[derive(Clone)]
+pub struct UploadSession {
+    client: Client,
+    uuid: Uuid,
+    parts: Vec<UploadPartUrl>,
+    completed: BTreeMap<u32, CompletedPart>,
+}
+
+impl UploadSession {
+    /// The UUID of the upload, which is used to submit the package once the upload is finished.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// All parts of the upload, including the ones which have been uploaded already.
+    pub fn parts(&self) -> &[UploadPartUrl] {
+        &self.parts
+    }
+
+    /// The parts which haven't been uploaded yet.
+    pub fn remaining_parts(&self) -> impl Iterator<Item = &UploadPartUrl> {
+        self.parts
+            .iter()
+            .filter(|part| !self.completed.contains_key(&part.part_number))
+    }
+
+    /// The parts which have been uploaded, ordered by part number.
+    ///
+    /// Save these along with [`UploadSession::uuid`] and [`UploadSession::parts`] to resume
+    /// the upload later with [`Client::resume_upload_session`].
+    pub fn completed_parts(&self) -> Vec<CompletedPart> {
+        self.completed.values().cloned().collect()
+    }
+
+    /// Whether every part has been uploaded, so the session can be finished.
+    pub fn is_complete(&self) -> bool {
+        self.completed.len() == self.parts.len()
+    }
+
+    /// Uploads a single part of the package.
+    ///
+    /// - `part_number` is the [`UploadPartUrl::part_number`] of the part.
+    /// - `data` must be exactly the [`UploadPartUrl::length`] bytes of the package
+    ///   starting at [`UploadPartUrl::offset`].
+    ///
+    /// Uploading a part again replaces it. Returns [`Error::InvalidUploadPart`] if the
+    /// upload doesn't have the part, or if `data` has the wrong length.
+    pub async fn upload_part(&mut self, part_number: u32, data: impl Into<Bytes>) -> Result<()> {
+        let data = data.into();
+        let part = self
+            .parts
+            .iter()
+            .find(|part| part.part_number == part_number)
+            .filter(|part| part.length == data.len() as u64)
+            .ok_or(Error::InvalidUploadPart { part_number })?;
+
+        let request = self.client.client.put(&part.url).body(data);
+        let completed = self.client.upload_chunk(request, part_number).await?;
+
+        self.completed.insert(part_number, completed);
+        Ok(())
+    }
+
+    /// Finalizes the upload with [`Client::finish_upload`].
+    ///
+    /// Returns [`Error::InvalidUploadParts`] without making a request if some parts
+    /// haven't been uploaded yet. Note that this doesn't submit the package, use
+    /// [`Client::submit_package`] with [`UploadSession::uuid`] for that.
+    pub async fn finish(self) -> Result<UserMedia> {
+        if !self.is_complete() {
+            return Err(Error::InvalidUploadParts);
+        }
+
+        let parts = self.completed.into_values().collect();
+        self.client.finish_upload(self.uuid, parts).await
+    }
+
+    /// Aborts the upload on the server with [`Client::abort_upload`].
+    pub async fn abort(self) -> Result<UserMedia> {
+        self.client.abort_upload(self.uuid).await
+    }
+}
+
+impl std::fmt::Debug for UploadSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadSession")
+            .field("uuid", &self.uuid)
+            .field("parts", &self.parts)
+            .field("completed", &self.completed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the parts in `upload_urls` which are not in `completed`,
+/// or an error if the two lists don't belong to the same upload.
+pub(crate) fn remaining_parts(
+    upload_urls: Vec<UploadPartUrl>,
+    completed: &[CompletedPart],
+) -> Result<Vec<UploadPartUrl>> {
+    let mut url_numbers = HashSet::new();
+    if !upload_urls
+        .iter()
+        .all(|part| url_numbers.insert(part.part_number))
+    {
+        return Err(Error::InvalidUploadParts);
+    }
+
+    let mut completed_numbers = HashSet::new();
+    for part in completed {
+        if !url_numbers.contains(&part.part_number) || !completed_numbers.insert(part.part_number) {
+            return Err(Error::InvalidUploadParts);
+        }
+    }
+
+    Ok(upload_urls
+        .into_iter()
+        .filter(|part| !completed_numbers.contains(&part.part_number))
+        .collect())
+}
+
+/// Returns the bytes of `data` which belong to `part`, or an error
+/// if the part's range is out of bounds.
+pub(crate) fn part_slice(data: &Bytes, part: &UploadPartUrl) -> Result<Bytes> {
+    let invalid = || Error::InvalidUploadPart {
         part_number: part.part_number,
-    })
+    };
+
+    let start = usize::try_from(part.offset).map_err(|_| invalid())?;
+    let end = part
+        .offset
+        .checked_add(part.length)
+        .and_then(|end| usize::try_from(end).ok())
+        .ok_or_else(invalid)?;
+
+    if end > data.len() {
+        return Err(invalid());
+    }
+
+    Ok(data.slice(start..end))
 }
 
 /// Metadata for a package submission.
@@ -238,6 +621,41 @@ impl PackageMetadata {
         }
     }
 
+    /// Creates a new package metadata object from a parsed `manifest.json`.
+    ///
+    /// The author is taken from [`PackageManifest::author`], and `communities` is the same
+    /// as in [`PackageMetadata::new`]. Categories and the NSFW flag aren't part of the manifest
+    /// format, so they still have to be set with the builder methods.
+    ///
+    /// Returns `None` if the manifest doesn't specify an author.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use thunderstore::{models::PackageManifest, usermedia::PackageMetadata};
+    ///
+    /// # #[cfg(feature = "zip")]
+    /// # fn run() -> thunderstore::Result<()> {
+    /// let data = std::fs::read("CoolMod.zip")?;
+    /// let manifest = PackageManifest::from_zip_bytes(&data)?;
+    ///
+    /// let metadata = PackageMetadata::from_manifest(&manifest, ["lethal-company"])
+    ///     .expect("manifest has no author")
+    ///     .with_global_categories(["mods"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_manifest<C>(
+        manifest: &PackageManifest,
+        communities: impl IntoIterator<Item = C>,
+    ) -> Option<Self>
+    where
+        C: Into<String>,
+    {
+        let author = manifest.author.as_deref()?;
+        Some(Self::new(author, communities))
+    }
+
     /// Adds a list of site-wide categories to the package.
     ///
     /// Categories are referred to by their slug, *not* the display name!
@@ -326,6 +744,7 @@ impl PackageMetadata {
     /// PackageMetadata::new("author", ["lethal-company", "content-warning"])
     ///     .with_categories("lethal-company", ["items"])
     ///     .with_categories("content-warning", ["emotes", "camera"]);
+    /// ```
     pub fn with_categories<C>(
         mut self,
         community: impl Into<String>,
@@ -340,4 +759,39 @@ impl PackageMetadata {
             .extend(categories.into_iter().map(Into::into));
         self
     }
+
+    /// Adds community-specific categories for many communities at once,
+    /// for example from a `HashMap<String, Vec<String>>`.
+    ///
+    /// The categories are merged with any that were added before, like with
+    /// [`PackageMetadata::with_categories`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use thunderstore::usermedia::PackageMetadata;
+    ///
+    /// let categories = HashMap::from([
+    ///     ("lethal-company", vec!["items"]),
+    ///     ("content-warning", vec!["emotes", "camera"]),
+    /// ]);
+    ///
+    /// PackageMetadata::new("author", ["lethal-company", "content-warning"])
+    ///     .with_community_categories(categories);
+    /// ```
+    pub fn with_community_categories<K, I, C>(
+        mut self,
+        categories: impl IntoIterator<Item = (K, I)>,
+    ) -> Self
+    where
+        K: Into<String>,
+        I: IntoIterator<Item = C>,
+        C: Into<String>,
+    {
+        for (community, community_categories) in categories {
+            self = self.with_categories(community, community_categories);
+        }
+        self
+    }
 }
@@ -0,0 +1,164 @@
+//! Incremental parsing of large JSON responses.
+
+use crate::Result;
+use async_stream::try_stream;
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_util::{pin_mut, TryStreamExt};
+
+/// How [`json_stream`] splits a response body into JSON values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Boundary {
+    /// One value per line, like the package index.
+    Lines,
+    /// The elements of a top-level array, like the v1 package listing.
+    ArrayElements,
+}
+
+/// Splits a stream of bytes into JSON values and passes each one to `parse`,
+/// yielding the items it returns. Values for which `parse` returns `None` are skipped.
+///
+/// Values may be split across any number of chunks, including in the middle
+/// of a string or a multi-byte character.
+pub(crate) fn json_stream<T, S, F>(
+    bytes: S,
+    boundary: Boundary,
+    mut parse: F,
+) -> impl Stream<Item = Result<T>>
+where
+    S: Stream<Item = Result<Bytes>>,
+    F: FnMut(&[u8]) -> Result<Option<T>>,
+{
+    try_stream! {
+        pin_mut!(bytes);
+        let mut splitter = Splitter::new(boundary);
+
+        while let Some(chunk) = bytes.try_next().await? {
+            for value in splitter.push(&chunk) {
+                if let Some(item) = parse(&value)? {
+                    yield item;
+                }
+            }
+        }
+
+        if let Some(value) = splitter.finish() {
+            if let Some(item) = parse(&value)? {
+                yield item;
+            }
+        }
+    }
+}
+
+/// Finds the boundaries between JSON values in a body which arrives in chunks.
+#[derive(Debug)]
+pub(crate) struct Splitter {
+    boundary: Boundary,
+    buffer: Vec<u8>,
+    /// Index in `buffer` of the next byte to scan.
+    pos: usize,
+    /// Index in `buffer` where the current value starts.
+    start: usize,
+    /// Nesting depth, where the top-level array counts as 1.
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    done: bool,
+}
+
+impl Splitter {
+    pub(crate) fn new(boundary: Boundary) -> Self {
+        Self {
+            boundary,
+            buffer: Vec::new(),
+            pos: 0,
+            start: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            done: false,
+        }
+    }
+
+    /// Adds a chunk of the body and returns the values which were completed by it.
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut values = Vec::new();
+
+        while self.pos < self.buffer.len() && !self.done {
+            let byte = self.buffer[self.pos];
+            self.pos += 1;
+
+            let end = match self.boundary {
+                Boundary::Lines => byte == b'\n',
+                Boundary::ArrayElements => self.scan_array(byte),
+            };
+
+            if end {
+                // the boundary byte itself isn't part of the value
+                self.take_value(self.pos - 1, &mut values);
+                self.start = self.pos;
+            }
+        }
+
+        // drop the bytes of values which have already been returned
+        self.buffer.drain(..self.start);
+        self.pos -= self.start;
+        self.start = 0;
+
+        values
+    }
+
+    /// Returns the trailing value, if any, once the whole body has been pushed.
+    pub(crate) fn finish(self) -> Option<Vec<u8>> {
+        if self.done {
+            return None;
+        }
+
+        let mut values = Vec::new();
+        self.take_value(self.buffer.len(), &mut values);
+        values.pop()
+    }
+
+    /// Updates the array state with the next byte and returns whether it ends an element.
+    fn scan_array(&mut self, byte: u8) -> bool {
+        if self.in_string {
+            match byte {
+                _ if self.escaped => self.escaped = false,
+                b'\\' => self.escaped = true,
+                b'"' => self.in_string = false,
+                _ => (),
+            }
+            return false;
+        }
+
+        match byte {
+            b'"' => self.in_string = true,
+            b'[' if self.depth == 0 => {
+                // the opening bracket of the array isn't part of the first element
+                self.depth = 1;
+                self.start = self.pos;
+            }
+            b'{' | b'[' => self.depth += 1,
+            b'}' | b']' => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    self.done = true;
+                    return true;
+                }
+            }
+            b',' if self.depth == 1 => return true,
+            _ => (),
+        }
+
+        false
+    }
+
+    /// Pushes `buffer[start..end]` to `values`, unless it's only whitespace.
+    fn take_value(&self, end: usize, values: &mut Vec<Vec<u8>>) {
+        let value = self.buffer[self.start..end].trim_ascii();
+        if !value.is_empty() {
+            values.push(value.to_vec());
+        }
+    }
+}
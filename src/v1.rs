@@ -1,7 +1,14 @@
-use crate::{models::*, Client, IntoPackageId, IntoVersionId, ResponseExt, Result};
-use async_stream::try_stream;
+use crate::{
+    models::*,
+    util::{json_stream, Boundary},
+    Client, Error, IntoPackageId, IntoVersionId, PackageId, ResponseJsonExt, Result, VersionId,
+    BATCH_CONCURRENCY,
+};
 use futures_core::Stream;
-use std::fmt::Display;
+use futures_util::{future, pin_mut, stream, StreamExt, TryStreamExt};
+use reqwest::Method;
+use std::{collections::HashMap, fmt::Display};
+use url::Url;
 
 impl Client {
     /// Fetches [`PackageMetrics`] for a specific package.
@@ -16,13 +23,118 @@ impl Client {
             community,
             format_args!("package-metrics/{}", package.into_id()?.path()),
         );
-        let response = self.client.get(&url).send().await.handle()?.json().await?;
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
         Ok(response)
     }
 
+    /// Fetches [`PackageMetrics`] for many packages concurrently.
+    ///
+    /// `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// At most [`BATCH_CONCURRENCY`] requests are in flight at once.
+    /// The results are in the same order as `packages`, and a failed request only
+    /// affects the result of its own package. Returns an error without sending any
+    /// requests if one of the ids is invalid.
+    pub async fn get_metrics_batch<I>(
+        &self,
+        community: impl Display,
+        packages: I,
+    ) -> Result<Vec<(PackageId, Result<PackageMetrics>)>>
+    where
+        I: IntoIterator,
+        I::Item: IntoPackageId,
+    {
+        let community = community.to_string();
+        let packages = packages
+            .into_iter()
+            .map(IntoPackageId::into_id)
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = stream::iter(packages)
+            .map(|package| async {
+                let metrics = self.get_metrics(&community, package.clone()).await;
+                (package, metrics)
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok(results)
+    }
+
+    /// Checks which of the installed packages have a newer version available.
+    ///
+    /// `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// The latest versions are looked up with [`Client::get_metrics_batch`], so at most
+    /// [`BATCH_CONCURRENCY`] requests are in flight at once. The results are in the same order
+    /// as `installed`, except that packages which aren't listed in the community are left out.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let installed = ["BepInEx-BepInExPack-5.4.2100", "Kesomannen-GaleModManager-0.6.0"];
+    ///
+    /// for info in client.check_updates("lethal-company", installed).await? {
+    ///     if info.update_available {
+    ///         println!("{} can be updated to {}", info.current, info.latest);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn check_updates<I>(
+        &self,
+        community: impl Display,
+        installed: I,
+    ) -> Result<Vec<UpdateInfo>>
+    where
+        I: IntoIterator,
+        I::Item: IntoVersionId,
+    {
+        let installed = installed
+            .into_iter()
+            .map(IntoVersionId::into_id)
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = self
+            .get_metrics_batch(community, installed.iter().map(PackageId::from))
+            .await?;
+
+        let mut updates = Vec::with_capacity(installed.len());
+
+        for (current, (_, metrics)) in installed.into_iter().zip(results) {
+            let metrics = match metrics {
+                Ok(metrics) => metrics,
+                Err(Error::NotFound { .. }) => continue,
+                Err(err) => return Err(err),
+            };
+
+            let update_available = metrics.latest_version > current.parsed_version()?;
+            let latest =
+                VersionId::from((current.namespace(), current.name(), &metrics.latest_version));
+
+            updates.push(UpdateInfo {
+                current,
+                latest,
+                update_available,
+            });
+        }
+
+        Ok(updates)
+    }
+
     /// Fetches the download count for a specific version of a package.
     ///
     /// `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// This shares the `package-metrics` route with [`Client::get_metrics`], but the
+    /// version number is appended as an extra path segment
+    /// (`package-metrics/{namespace}/{name}/{version}/`), which makes the API return
+    /// the downloads of that version only, instead of the package-wide totals.
     pub async fn get_downloads(
         &self,
         community: impl Display,
@@ -33,10 +145,65 @@ impl Client {
             format_args!("package-metrics/{}", version.into_id()?.path()),
         );
         let response: PackageVersionMetrics =
-            self.client.get(&url).send().await.handle()?.json().await?;
+            self.send(self.client.get(&url)).await?.parse_json().await?;
         Ok(response.downloads)
     }
 
+    /// Fetches the download count of every version of a package.
+    ///
+    /// `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// The API has no endpoint for this, so one [`Client::get_downloads`] request is made
+    /// for each version in [`PackageV1::versions`], with at most [`BATCH_CONCURRENCY`]
+    /// in flight at once. Packages with many versions can therefore hit Thunderstore's rate limits.
+    /// If slightly outdated counts are fine, use [`PackageVersionV1::downloads`] instead.
+    pub async fn get_version_metrics(
+        &self,
+        community: impl Display,
+        package: &PackageV1,
+    ) -> Result<HashMap<VersionId, u64>> {
+        let community = community.to_string();
+
+        stream::iter(&package.versions)
+            .map(|version| {
+                let id = VersionId::from(version);
+                let community = &community;
+
+                async move {
+                    let downloads = self.get_downloads(community, id.clone()).await?;
+                    Ok((id, downloads))
+                }
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Likes or unlikes a package as the user who owns the API token.
+    ///
+    /// Returns the package's new rating score. Rating a package which is already
+    /// rated, or the opposite, doesn't change anything.
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn rate_package(&self, package: &PackageV1, rated: bool) -> Result<u32> {
+        let url = format!("{}/api/v1/package/{}/rate/", self.base_url, package.uuid4);
+        let params = RatePackageParams {
+            target_state: if rated {
+                RatingState::Rated
+            } else {
+                RatingState::Unrated
+            },
+        };
+
+        let response: RatePackageResponse = self
+            .send(self.auth_request(Method::POST, url)?.json(&params))
+            .await?
+            .parse_json()
+            .await?;
+
+        Ok(response.score)
+    }
+
     /// Fetches all available packages in a community and collects them in a `Vec`.
     ///
     /// - `community` is the slug of the community, which is usually in kebab-case.
@@ -45,10 +212,110 @@ impl Client {
     /// this will fetch up to 170 MB of data.
     pub async fn list_packages_v1(&self, community: impl Display) -> Result<Vec<PackageV1>> {
         let url = self.v1_url(community, "package");
-        let response = self.client.get(&url).send().await.handle()?.json().await?;
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
         Ok(response)
     }
 
+    /// Fetches all available packages in a community and sorts them.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// Like [`Client::list_packages_v1`], this fetches the whole listing before sorting it.
+    pub async fn list_packages_v1_sorted(
+        &self,
+        community: impl Display,
+        key: SortKey,
+        order: SortOrder,
+    ) -> Result<Vec<PackageV1>> {
+        let mut packages = self.list_packages_v1(community).await?;
+
+        packages.sort_by(|a, b| match order {
+            SortOrder::Ascending => key.compare(a, b),
+            SortOrder::Descending => key.compare(b, a),
+        });
+
+        Ok(packages)
+    }
+
+    /// Fetches all packages owned by a namespace (team) in a community.
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    /// - `namespace` is the name of the team, compared case-insensitively.
+    ///
+    /// Packages are listed per community, so this only finds packages which are
+    /// available in `community`. The v1 API has no way to filter by namespace,
+    /// so this streams the whole listing and filters it on the client.
+    pub async fn list_packages_by_namespace(
+        &self,
+        community: impl Display,
+        namespace: impl AsRef<str>,
+    ) -> Result<Vec<PackageV1>> {
+        let namespace = namespace.as_ref();
+
+        self.stream_packages_v1(community)
+            .await?
+            .try_filter(|package| future::ready(package.owner.eq_ignore_ascii_case(namespace)))
+            .try_collect()
+            .await
+    }
+
+    /// Fetches the download URL of every version of a package.
+    ///
+    /// - `community` is the slug of a community the package is listed in.
+    ///
+    /// The URLs come from [`PackageVersionV1::download_url`], so this makes a single request
+    /// instead of one per version. There's no v1 endpoint for a single package by name, so
+    /// the community's listing is streamed until the package is found.
+    ///
+    /// Returns [`Error::NotFound`] if the package isn't listed in `community`.
+    pub async fn get_all_download_urls(
+        &self,
+        community: impl Display,
+        package: impl IntoPackageId,
+    ) -> Result<Vec<(VersionId, Url)>> {
+        let id = package.into_id()?;
+
+        let stream = self.stream_packages_v1(community).await?;
+        pin_mut!(stream);
+
+        while let Some(package) = stream.try_next().await? {
+            if package.full_name == id.as_str() {
+                return Ok(package
+                    .versions
+                    .iter()
+                    .map(|version| (VersionId::from(version), version.download_url.clone()))
+                    .collect());
+            }
+        }
+
+        Err(Error::NotFound {
+            resource: Some(id.into_string()),
+        })
+    }
+
+    /// Like [`Client::list_packages_v1`], but uses the default community.
+    ///
+    /// Returns [`Error::NoCommunitySet`] if the client doesn't have a default community,
+    /// see [`ClientBuilder::with_default_community`](crate::ClientBuilder::with_default_community).
+    pub async fn list_packages(&self) -> Result<Vec<PackageV1>> {
+        self.list_packages_v1(self.require_community()?).await
+    }
+
+    /// Like [`Client::stream_packages_v1`], but uses the default community.
+    ///
+    /// Returns [`Error::NoCommunitySet`] if the client doesn't have a default community.
+    pub async fn stream_packages(&self) -> Result<impl Stream<Item = Result<PackageV1>>> {
+        self.stream_packages_v1(self.require_community()?.clone())
+            .await
+    }
+
+    /// Like [`Client::get_metrics`], but uses the default community.
+    ///
+    /// Returns [`Error::NoCommunitySet`] if the client doesn't have a default community.
+    pub async fn get_package_metrics(&self, package: impl IntoPackageId) -> Result<PackageMetrics> {
+        self.get_metrics(self.require_community()?, package).await
+    }
+
     fn v1_url(&self, community: impl Display, tail: impl Display) -> String {
         format!("{}/c/{}/api/v1/{}/", self.base_url, community, tail)
     }
@@ -86,38 +353,54 @@ impl Client {
         &self,
         community: impl Display,
     ) -> Result<impl Stream<Item = Result<PackageV1>>> {
-        let url = self.v1_url(community, "package");
-        let mut response = self.client.get(&url).send().await.handle()?;
-
-        Ok(try_stream! {
-            let mut buffer = Vec::new();
-            let mut string = String::new();
-
-            let mut is_first = true;
-
-            while let Some(chunk) = response.chunk().await? {
-                buffer.extend_from_slice(&chunk);
-
-                let chunk = match std::str::from_utf8(&buffer) {
-                    Ok(chunk) => chunk,
-                    Err(_) => continue,
-                };
-
-                if is_first {
-                    is_first = false;
-                    string.extend(chunk.chars().skip(1)); // remove leading [
-                } else {
-                    string.push_str(chunk);
-                }
+        self.stream_packages_v1_filtered(community, PackageFilter::default())
+            .await
+    }
 
-                buffer.clear();
+    /// Asynchronously streams the packages in a community which match a [`PackageFilter`].
+    ///
+    /// - `community` is the slug of the community, which is usually in kebab-case.
+    ///
+    /// The filter is checked before each package is fully deserialized,
+    /// so packages which don't match are skipped cheaply.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use futures_util::TryStreamExt;
+    /// use thunderstore::models::PackageFilter;
+    ///
+    /// # async fn run() -> thunderstore::Result<()> {
+    /// let client = thunderstore::Client::new();
+    ///
+    /// let filter = PackageFilter::new()
+    ///     .skip_deprecated()
+    ///     .skip_nsfw()
+    ///     .with_category("Modpacks");
+    ///
+    /// let modpacks: Vec<_> = client
+    ///     .stream_packages_v1_filtered("lethal-company", filter)
+    ///     .await?
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream_packages_v1_filtered(
+        &self,
+        community: impl Display,
+        filter: PackageFilter,
+    ) -> Result<impl Stream<Item = Result<PackageV1>>> {
+        let url = self.v1_url(community, "package");
+        let response = self.send(self.client.get(&url)).await?;
+        let bytes = response.bytes_stream().map_err(Error::from);
 
-                while let Some(index) = string.find("}]},") {
-                    let (json, _) = string.split_at(index + 3);
-                    yield serde_json::from_str::<PackageV1>(json)?;
-                    string.replace_range(..index + 4, "");
-                }
+        Ok(json_stream(bytes, Boundary::ArrayElements, move |json| {
+            if !filter.matches_json(json)? {
+                return Ok(None);
             }
-        })
+
+            Ok(Some(serde_json::from_slice::<PackageV1>(json)?))
+        }))
     }
 }
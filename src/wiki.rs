@@ -0,0 +1,129 @@
+use crate::{
+    experimental::paginate, models::*, Client, Error, IntoPackageId, ResponseJsonExt, Result,
+};
+use futures_core::Stream;
+use reqwest::Method;
+use serde::Serialize;
+use std::fmt::Display;
+
+impl Client {
+    /// Fetches a page of package wikis, ordered by when they were last updated.
+    ///
+    /// - `cursor` is used to fetch the next page of results, and should be the
+    ///   [`WikisResponse::cursor`] of the previous page. Pass `None` to get the first page.
+    ///
+    /// If you want to go through all wikis, use [`Client::stream_wikis`] instead.
+    pub async fn get_wikis(&self, cursor: Option<&str>) -> Result<WikisResponse> {
+        let url = self.experimental_url("package/wikis");
+
+        let mut request = self.client.get(&url);
+        if let Some(cursor) = cursor {
+            request = request.query(&[("after", cursor)]);
+        }
+
+        let response = self.send(request).await?.parse_json().await?;
+        Ok(response)
+    }
+
+    /// Asynchronously streams all package wikis, fetching pages as they are needed.
+    pub fn stream_wikis(&self) -> impl Stream<Item = Result<ListedWiki>> + '_ {
+        paginate(move |cursor| async move {
+            let response = self.get_wikis(cursor.as_deref()).await?;
+
+            Ok(Page {
+                cursor: CursorState {
+                    next: response.cursor.filter(|_| response.has_more),
+                    prev: None,
+                },
+                items: response.results,
+            })
+        })
+    }
+
+    /// Fetches the wiki of a package.
+    ///
+    /// Returns [`Error::NotFound`] if the package doesn't have a wiki.
+    pub async fn get_wiki(&self, package: impl IntoPackageId) -> Result<Wiki> {
+        let url = self.experimental_url(format_args!("package/{}/wiki", package.into_id()?.path()));
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
+        Ok(response)
+    }
+
+    /// Fetches the id and title of each page in a package's wiki, in the order they're listed.
+    ///
+    /// This is a projection of [`Client::get_wiki`], which only lists the pages without
+    /// their content, so it takes a single small request regardless of the wiki's size.
+    /// Use [`Client::get_wiki_page`] to fetch the content of a page.
+    ///
+    /// Returns [`Error::NotFound`] if the package doesn't have a wiki.
+    pub async fn get_wiki_titles(
+        &self,
+        package: impl IntoPackageId,
+    ) -> Result<Vec<(String, String)>> {
+        let wiki = self.get_wiki(package).await?;
+
+        Ok(wiki
+            .pages
+            .into_iter()
+            .map(|page| (page.id, page.title))
+            .collect())
+    }
+
+    /// Fetches a single wiki page, including its content.
+    ///
+    /// - `id` is the id of the page, found in [`Wiki::pages`].
+    pub async fn get_wiki_page(&self, id: impl Display) -> Result<WikiPage> {
+        let url = self.experimental_url(format_args!("wiki/page/{}", id));
+        let response = self.send(self.client.get(&url)).await?.parse_json().await?;
+        Ok(response)
+    }
+
+    /// Deletes a page from a package's wiki and returns the id of the deleted page.
+    ///
+    /// - `page_id` is the id of the page, found in [`Wiki::pages`].
+    ///
+    /// Fails with [`Error::NotFound`] if the page doesn't exist, for example because it was
+    /// already deleted, and with [`Error::ApiTokenInvalid`] if the server rejects the token (401).
+    /// If the token is valid but isn't allowed to edit the wiki, the server responds with 403,
+    /// which is returned as [`Error::Client`].
+    ///
+    /// This method requires a valid API token on the client.
+    pub async fn delete_wiki_page(
+        &self,
+        package: impl IntoPackageId,
+        page_id: impl Into<String>,
+    ) -> Result<String> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct DeleteWikiPageParams {
+            page_id: String,
+        }
+
+        let url = self.experimental_url(format_args!("package/{}/wiki", package.into_id()?.path()));
+        let params = DeleteWikiPageParams {
+            page_id: page_id.into(),
+        };
+
+        self.send(self.auth_request(Method::DELETE, url)?.json(&params))
+            .await?;
+
+        Ok(params.page_id)
+    }
+
+    /// Fetches a wiki page and renders its content to HTML.
+    ///
+    /// Returns the page together with the rendered HTML, or [`Error::NotFound`]
+    /// if the page has no content.
+    pub async fn get_wiki_page_html(&self, id: impl Display) -> Result<(WikiPage, String)> {
+        let page = self.get_wiki_page(id).await?;
+
+        if page.content.trim().is_empty() {
+            return Err(Error::NotFound {
+                resource: Some(format!("content of wiki page {}", page.id)),
+            });
+        }
+
+        let html = self.render_markdown(&page.content).await?;
+        Ok((page, html))
+    }
+}